@@ -0,0 +1,16 @@
+# cd to the directory cb-ls was in when you quit with `q`.
+#
+# Add `source /path/to/cbcd.bash` to ~/.bashrc, then run `cbcd` instead of `cb-ls`.
+# Quitting with `Q` leaves the current directory alone.
+cbcd() {
+    local tmp dir rc
+    tmp="$(mktemp)" || return
+    command cb-ls --choosedir "$tmp" "$@"
+    rc=$?
+    dir="$(cat -- "$tmp")"
+    rm -f -- "$tmp"
+    if [ -n "$dir" ] && [ -d "$dir" ] && [ "$dir" != "$PWD" ]; then
+        cd -- "$dir" || return
+    fi
+    return $rc
+}
@@ -0,0 +1,15 @@
+# cd to the directory cb-ls was in when you quit with `q`.
+#
+# Copy to ~/.config/fish/functions/cbcd.fish, then run `cbcd` instead of `cb-ls`.
+# Quitting with `Q` leaves the current directory alone.
+function cbcd --description 'Browse with cb-ls and cd to the last directory'
+    set -l tmp (mktemp); or return
+    command cb-ls --choosedir $tmp $argv
+    set -l exit_status $status
+    set -l dir (cat -- $tmp)
+    rm -f -- $tmp
+    if test -n "$dir" -a -d "$dir" -a "$dir" != "$PWD"
+        cd -- $dir
+    end
+    return $exit_status
+end
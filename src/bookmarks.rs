@@ -0,0 +1,114 @@
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+/// A directory saved under a single key with `m<key>`, jumped to with `'<key>`.
+pub struct Bookmark {
+    pub key: char,
+    pub name: String,
+    pub path: PathBuf,
+}
+
+/// The user's bookmarks, sorted by key.
+///
+/// Kept in `$XDG_DATA_HOME/cb-ls/bookmarks`, one `key<TAB>path<TAB>name`
+/// per line.
+#[derive(Default)]
+pub struct Bookmarks {
+    pub marks: Vec<Bookmark>,
+}
+
+impl Bookmarks {
+    /// Marks that always exist: home, root and the previous directory.
+    pub const BUILT_IN: [char; 3] = ['~', '/', '\''];
+
+    pub fn default_path() -> Option<PathBuf> {
+        Some(crate::config::data_dir()?.join("bookmarks"))
+    }
+
+    /// Reads the bookmarks, a missing file is no bookmarks and broken lines are skipped.
+    pub fn load(path: &Path) -> Bookmarks {
+        let mut bookmarks = Bookmarks::default();
+        let Ok(content) = fs::read_to_string(path) else {
+            return bookmarks;
+        };
+
+        for line in content.lines() {
+            let mut fields = line.splitn(3, '\t');
+            let (Some(key), Some(path)) = (fields.next(), fields.next()) else {
+                continue;
+            };
+            let mut chars = key.chars();
+            let (Some(key), None) = (chars.next(), chars.next()) else {
+                continue;
+            };
+
+            let path = PathBuf::from(path);
+            let name = fields.next().map(str::to_string);
+            if bookmarks.set(key, &path).is_ok()
+                && let Some(name) = name
+            {
+                bookmarks.rename(key, &name);
+            }
+        }
+        bookmarks
+    }
+
+    pub fn get(&self, key: char) -> Option<&Bookmark> {
+        self.marks.iter().find(|mark| mark.key == key)
+    }
+
+    /// Points `key` at `path`, named after the directory.
+    pub fn set(&mut self, key: char, path: &Path) -> Result<(), String> {
+        if Bookmarks::BUILT_IN.contains(&key) {
+            return Err(format!("`{}` is a built-in mark", key));
+        }
+        if key.is_control() || key.is_whitespace() {
+            return Err(format!(
+                "`{}` can't be used as a mark",
+                key.escape_default()
+            ));
+        }
+
+        let name = path
+            .file_name()
+            .map(|name| name.to_string_lossy().to_string())
+            .unwrap_or_else(|| path.to_string_lossy().to_string());
+
+        self.remove(key);
+        let index = self.marks.partition_point(|mark| mark.key < key);
+        self.marks.insert(
+            index,
+            Bookmark {
+                key,
+                name,
+                path: path.to_path_buf(),
+            },
+        );
+        Ok(())
+    }
+
+    pub fn rename(&mut self, key: char, name: &str) {
+        if let Some(mark) = self.marks.iter_mut().find(|mark| mark.key == key) {
+            mark.name = name.replace(['\t', '\n'], " ");
+        }
+    }
+
+    pub fn remove(&mut self, key: char) {
+        self.marks.retain(|mark| mark.key != key);
+    }
+
+    pub fn save(&self, path: &Path) -> io::Result<()> {
+        crate::config::write_data_file(
+            path,
+            self.marks.iter().map(|mark| {
+                format!(
+                    "{}\t{}\t{}",
+                    mark.key,
+                    mark.path.to_string_lossy(),
+                    mark.name
+                )
+            }),
+        )
+    }
+}
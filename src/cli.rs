@@ -0,0 +1,220 @@
+use crate::sort::SortMode;
+use std::path::PathBuf;
+
+pub const USAGE: &str = "\
+Usage: cb-ls [OPTIONS] [PATH]
+
+A small terminal file explorer. Starts in PATH, or the current directory.
+Quit with q, or with Q to skip --choosedir/--print-last-dir.
+
+Options:
+      --show-hidden      Show entries starting with a dot
+      --sort <MODE>      Sort by name, size, modified, extension or none
+      --config <FILE>    Read settings from FILE instead of ~/.config/cb-ls/config
+      --select <FILE>    Start with the cursor on FILE
+      --choosedir <FILE> Write the last browsed directory to FILE on exit
+      --print-last-dir   Print the last browsed directory on exit
+
+Picking (for use from editors and scripts):
+      --pick             Print the chosen paths instead of opening them, exit 1 on cancel
+      --pick-dirs        Only allow picking directories (implies --pick)
+      --pick-ext <EXTS>  Only allow picking files ending in EXTS, e.g. png,jpg (implies --pick)
+      --choosefiles <FILE>
+                         Write the chosen paths to FILE instead of stdout (implies --pick)
+  -0, --print0           Separate the chosen paths with NUL instead of newlines
+
+Directory jumper (z in cb-ls):
+      --import-z <FILE>  Add the directories of a z database (~/.z) and exit
+      --import-zoxide    Add the directories known to zoxide and exit
+
+  -h, --help             Print this help and exit
+  -V, --version          Print the version and exit
+";
+
+/// Everything that can be passed on the command line.
+#[derive(Default, Debug)]
+pub struct Args {
+    pub path: Option<PathBuf>,
+    pub show_hidden: bool,
+    pub sort: Option<SortMode>,
+    pub config: Option<PathBuf>,
+    pub select: Option<PathBuf>,
+    pub choosedir: Option<PathBuf>,
+    pub print_last_dir: bool,
+    pub pick: bool,
+    pub pick_dirs: bool,
+    pub pick_ext: Vec<String>,
+    pub choosefiles: Option<PathBuf>,
+    pub print0: bool,
+    pub import_z: Option<PathBuf>,
+    pub import_zoxide: bool,
+    pub help: bool,
+    pub version: bool,
+}
+
+pub fn parse(args: impl IntoIterator<Item = String>) -> Result<Args, String> {
+    let mut parsed = Args::default();
+    let mut args = args.into_iter();
+
+    while let Some(arg) = args.next() {
+        // accept both `--sort size` and `--sort=size`
+        let (flag, inline_value) = match arg.split_once('=') {
+            Some((flag, value)) if flag.starts_with("--") => {
+                (flag.to_string(), Some(value.to_string()))
+            }
+            _ => (arg.clone(), None),
+        };
+        let mut value = |name: &str| {
+            inline_value
+                .clone()
+                .or_else(|| args.next())
+                .ok_or_else(|| format!("{} needs a value", name))
+        };
+        // on/off flags take no value, `--show-hidden=false` is a mistake
+        let switch = || match inline_value {
+            Some(_) => Err(format!("{} doesn't take a value", flag)),
+            None => Ok(true),
+        };
+
+        match flag.as_str() {
+            "-h" | "--help" => parsed.help = switch()?,
+            "-V" | "--version" => parsed.version = switch()?,
+            "--show-hidden" => parsed.show_hidden = switch()?,
+            "--sort" => {
+                let mode = value("--sort")?;
+                parsed.sort = Some(SortMode::from_name(&mode).ok_or_else(|| {
+                    format!(
+                        "unknown sort mode `{}` (expected {})",
+                        mode,
+                        SortMode::NAMES
+                    )
+                })?);
+            }
+            "--config" => parsed.config = Some(PathBuf::from(value("--config")?)),
+            "--select" => parsed.select = Some(PathBuf::from(value("--select")?)),
+            "--choosedir" => parsed.choosedir = Some(PathBuf::from(value("--choosedir")?)),
+            "--print-last-dir" => parsed.print_last_dir = switch()?,
+            "--pick" => parsed.pick = switch()?,
+            "--pick-dirs" => {
+                parsed.pick = switch()?;
+                parsed.pick_dirs = true;
+            }
+            "--pick-ext" => {
+                parsed.pick = true;
+                parsed.pick_ext.extend(
+                    value("--pick-ext")?
+                        .split(',')
+                        .map(|ext| ext.trim().trim_start_matches('.').to_lowercase())
+                        .filter(|ext| !ext.is_empty()),
+                );
+            }
+            "--choosefiles" => {
+                parsed.pick = true;
+                parsed.choosefiles = Some(PathBuf::from(value("--choosefiles")?));
+            }
+            "-0" | "--print0" => parsed.print0 = switch()?,
+            "--import-z" => parsed.import_z = Some(PathBuf::from(value("--import-z")?)),
+            "--import-zoxide" => parsed.import_zoxide = switch()?,
+            "--" => {
+                switch()?;
+                if let Some(path) = args.next() {
+                    set_path(&mut parsed, path)?;
+                }
+            }
+            _ if flag.starts_with('-') && flag.len() > 1 => {
+                return Err(format!("unknown option `{}`", arg));
+            }
+            _ => set_path(&mut parsed, arg)?,
+        }
+    }
+
+    if parsed.pick_dirs && !parsed.pick_ext.is_empty() {
+        return Err("--pick-dirs and --pick-ext can't be used together".to_string());
+    }
+
+    Ok(parsed)
+}
+
+fn set_path(parsed: &mut Args, path: String) -> Result<(), String> {
+    if parsed.path.is_some() {
+        return Err(format!("unexpected argument `{}`", path));
+    }
+    parsed.path = Some(PathBuf::from(path));
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse_args(args: &[&str]) -> Result<Args, String> {
+        parse(args.iter().map(|arg| arg.to_string()))
+    }
+
+    #[test]
+    fn flag_with_inline_value() {
+        let args = parse_args(&["--sort=size", "--select=a.txt"]).unwrap();
+        assert_eq!(args.sort, Some(SortMode::Size));
+        assert_eq!(args.select, Some(PathBuf::from("a.txt")));
+    }
+
+    #[test]
+    fn flag_with_separate_value() {
+        let args = parse_args(&["--sort", "modified", "/tmp"]).unwrap();
+        assert_eq!(args.sort, Some(SortMode::Modified));
+        assert_eq!(args.path, Some(PathBuf::from("/tmp")));
+    }
+
+    #[test]
+    fn double_dash_takes_the_next_argument_as_path() {
+        let args = parse_args(&["--", "--show-hidden"]).unwrap();
+        assert_eq!(args.path, Some(PathBuf::from("--show-hidden")));
+        assert!(!args.show_hidden);
+    }
+
+    #[test]
+    fn missing_value() {
+        let err = parse_args(&["--sort"]).unwrap_err();
+        assert_eq!(err, "--sort needs a value");
+    }
+
+    #[test]
+    fn unknown_option() {
+        let err = parse_args(&["--frobnicate"]).unwrap_err();
+        assert_eq!(err, "unknown option `--frobnicate`");
+    }
+
+    #[test]
+    fn unknown_option_with_value_is_reported_whole() {
+        let err = parse_args(&["--frobnicate=1"]).unwrap_err();
+        assert_eq!(err, "unknown option `--frobnicate=1`");
+    }
+
+    #[test]
+    fn switches_reject_inline_values() {
+        for arg in ["--show-hidden=false", "--print-last-dir=x", "--pick-dirs=1"] {
+            let err = parse_args(&[arg]).unwrap_err();
+            let flag = arg.split_once('=').unwrap().0;
+            assert_eq!(err, format!("{} doesn't take a value", flag));
+        }
+    }
+
+    #[test]
+    fn switches_leave_the_next_argument_alone() {
+        let args = parse_args(&["--show-hidden", "false"]).unwrap();
+        assert!(args.show_hidden);
+        assert_eq!(args.path, Some(PathBuf::from("false")));
+    }
+
+    #[test]
+    fn unknown_sort_mode() {
+        let err = parse_args(&["--sort=colour"]).unwrap_err();
+        assert!(err.starts_with("unknown sort mode `colour`"), "{}", err);
+    }
+
+    #[test]
+    fn second_path_is_rejected() {
+        let err = parse_args(&["a", "b"]).unwrap_err();
+        assert_eq!(err, "unexpected argument `b`");
+    }
+}
@@ -0,0 +1,53 @@
+use std::path::{Path, PathBuf};
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum ClipMode {
+    /// `y`, pasting copies.
+    Yank,
+    /// `x`, pasting moves.
+    Cut,
+}
+
+/// Files yanked or cut, waiting to be pasted with `p`.
+///
+/// Unlike the selection it is a snapshot: selecting other files afterwards
+/// doesn't change what gets pasted.
+pub struct Clipboard {
+    pub mode: ClipMode,
+    pub items: Vec<String>,
+}
+
+impl Clipboard {
+    pub fn is_cut(&self, item: &str) -> bool {
+        self.mode == ClipMode::Cut && self.items.iter().any(|cut| cut == item)
+    }
+}
+
+/// Where `name` can be pasted into `dir` without overwriting anything:
+/// `name`, or `name_1`, `name_2`… before the extension.
+pub fn free_destination(dir: &Path, name: &str) -> PathBuf {
+    let destination = dir.join(name);
+    if std::fs::symlink_metadata(&destination).is_err() {
+        return destination;
+    }
+
+    // hidden files like `.bashrc` have no extension
+    let (stem, ext) = match name.rfind('.') {
+        Some(index) if index > 0 => (&name[..index], &name[index..]),
+        _ => (name, ""),
+    };
+
+    (1..)
+        .map(|n| dir.join(format!("{}_{}{}", stem, n, ext)))
+        .find(|destination| std::fs::symlink_metadata(destination).is_err())
+        .unwrap_or(destination)
+}
+
+/// How `p`, `P` and `^p` put the clipboard into a directory.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum PasteMode {
+    /// Copy or move, depending on the [`ClipMode`].
+    Files,
+    Symlink,
+    Hardlink,
+}
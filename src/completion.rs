@@ -0,0 +1,64 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Turns what was typed at the `g` prompt into a path: `~` is the home
+/// directory and relative paths start at `base`.
+pub fn expand(input: &str, base: &Path) -> PathBuf {
+    let home = || std::env::var_os("HOME").map(PathBuf::from);
+
+    let path = match input.strip_prefix('~') {
+        Some("") => home(),
+        Some(rest) if rest.starts_with('/') => home().map(|home| home.join(&rest[1..])),
+        _ => None,
+    }
+    .unwrap_or_else(|| PathBuf::from(input));
+
+    base.join(path)
+}
+
+/// Ways to complete the last component of `input` to a directory, as the
+/// whole input with a trailing `/`, sorted.
+///
+/// Hidden directories are only offered once the component starts with a dot.
+pub fn candidates(input: &str, base: &Path) -> Vec<String> {
+    let (dir, prefix) = match input.rfind('/') {
+        Some(index) => (&input[..=index], &input[index + 1..]),
+        None if input == "~" => return vec!["~/".to_string()],
+        None => ("", input),
+    };
+
+    let Ok(entries) = fs::read_dir(expand(dir, base)) else {
+        return Vec::new();
+    };
+
+    let mut candidates: Vec<String> = entries
+        .filter_map(Result::ok)
+        .filter(|entry| entry.path().is_dir())
+        .map(|entry| entry.file_name().to_string_lossy().to_string())
+        .filter(|name| {
+            name.starts_with(prefix) && (prefix.starts_with('.') || !name.starts_with('.'))
+        })
+        .map(|name| format!("{}{}/", dir, name))
+        .collect();
+
+    candidates.sort_by(|a, b| natord::compare(a, b));
+    candidates
+}
+
+/// The longest start all `candidates` share.
+pub fn common_prefix(candidates: &[String]) -> String {
+    let Some(first) = candidates.first() else {
+        return String::new();
+    };
+
+    let mut prefix = first.as_str();
+    for candidate in &candidates[1..] {
+        let len = prefix
+            .char_indices()
+            .zip(candidate.chars())
+            .find(|((_, a), b)| a != b)
+            .map_or(prefix.len().min(candidate.len()), |((index, _), _)| index);
+        prefix = &prefix[..len];
+    }
+    prefix.to_string()
+}
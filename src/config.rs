@@ -0,0 +1,179 @@
+use crate::icons::IconSet;
+use crate::opener::Opener;
+use crate::sort::SortMode;
+use crate::system_clipboard::CopyMethod;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+/// Settings read from the config file.
+///
+/// The file is plain `key = value` lines, `#` starts a comment:
+///
+/// ```text
+/// show_hidden = true
+/// sort = modified
+/// icons = ascii
+/// clipboard = xclip
+/// keep_selection = true
+///
+/// [openers]
+/// ext pdf = zathura {path}
+/// ```
+///
+/// See [`Opener`] for the rule syntax of the `[openers]` section.
+#[derive(Default)]
+pub struct Config {
+    pub show_hidden: bool,
+    pub sort: SortMode,
+    pub icons: IconSet,
+    pub clipboard: CopyMethod,
+    /// Save the selection on exit and restore it on the next start.
+    pub keep_selection: bool,
+    pub openers: Vec<Opener>,
+}
+
+impl Config {
+    /// `$XDG_CONFIG_HOME/cb-ls/config`, or `~/.config/cb-ls/config`.
+    pub fn default_path() -> Option<PathBuf> {
+        let base = match std::env::var_os("XDG_CONFIG_HOME") {
+            Some(dir) if !dir.is_empty() => PathBuf::from(dir),
+            _ => PathBuf::from(std::env::var_os("HOME")?).join(".config"),
+        };
+        Some(base.join("cb-ls").join("config"))
+    }
+
+    /// Loads `path`, or the default config file if it exists.
+    pub fn load(path: Option<&Path>) -> Result<Config, String> {
+        let path = match path {
+            Some(path) => path.to_path_buf(),
+            None => match Config::default_path() {
+                Some(path) if path.is_file() => path,
+                _ => return Ok(Config::default()),
+            },
+        };
+
+        let content = fs::read_to_string(&path)
+            .map_err(|err| format!("cannot read config {}: {}", path.display(), err))?;
+
+        Config::parse(&content).map_err(|err| format!("{}: {}", path.display(), err))
+    }
+
+    pub fn parse(content: &str) -> Result<Config, String> {
+        let mut config = Config::default();
+        let mut section = String::new();
+
+        for (number, line) in content.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            if let Some(name) = line
+                .strip_prefix('[')
+                .and_then(|line| line.strip_suffix(']'))
+            {
+                section = name.trim().to_string();
+                continue;
+            }
+
+            match section.as_str() {
+                "" => {}
+                // commands may contain `#`, so only whole-line comments here
+                "openers" => {
+                    let opener = Opener::parse(line)
+                        .map_err(|err| format!("line {}: {}", number + 1, err))?;
+                    config.openers.push(opener);
+                    continue;
+                }
+                _ => {
+                    return Err(format!(
+                        "line {}: unknown section `[{}]`",
+                        number + 1,
+                        section
+                    ));
+                }
+            }
+
+            let line = line.split('#').next().unwrap_or("").trim();
+
+            let Some((key, value)) = line.split_once('=') else {
+                return Err(format!("line {}: expected `key = value`", number + 1));
+            };
+            let (key, value) = (key.trim(), value.trim());
+
+            match key {
+                "show_hidden" => config.show_hidden = parse_bool(value, number)?,
+                "keep_selection" => config.keep_selection = parse_bool(value, number)?,
+                "sort" => {
+                    config.sort = SortMode::from_name(value).ok_or_else(|| {
+                        format!(
+                            "line {}: unknown sort mode `{}` (expected {})",
+                            number + 1,
+                            value,
+                            SortMode::NAMES
+                        )
+                    })?
+                }
+                "icons" => {
+                    config.icons = IconSet::from_name(value).ok_or_else(|| {
+                        format!(
+                            "line {}: unknown icon set `{}` (expected nerd, ascii, off)",
+                            number + 1,
+                            value
+                        )
+                    })?
+                }
+                "clipboard" => {
+                    config.clipboard = CopyMethod::from_name(value).ok_or_else(|| {
+                        format!(
+                            "line {}: unknown clipboard `{}` (expected {})",
+                            number + 1,
+                            value,
+                            CopyMethod::NAMES
+                        )
+                    })?
+                }
+                _ => return Err(format!("line {}: unknown setting `{}`", number + 1, key)),
+            }
+        }
+
+        Ok(config)
+    }
+}
+
+/// `$XDG_DATA_HOME/cb-ls`, or `~/.local/share/cb-ls`, where state kept
+/// between sessions lives.
+pub fn data_dir() -> Option<PathBuf> {
+    let base = match std::env::var_os("XDG_DATA_HOME") {
+        Some(dir) if !dir.is_empty() => PathBuf::from(dir),
+        _ => PathBuf::from(std::env::var_os("HOME")?).join(".local/share"),
+    };
+    Some(base.join("cb-ls"))
+}
+
+/// Writes `lines` to a file under [`data_dir`], creating the directory first.
+pub fn write_data_file(path: &Path, lines: impl IntoIterator<Item = String>) -> io::Result<()> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+
+    let mut content = String::new();
+    for line in lines {
+        content.push_str(&line);
+        content.push('\n');
+    }
+    fs::write(path, content)
+}
+
+fn parse_bool(value: &str, number: usize) -> Result<bool, String> {
+    match value {
+        "true" | "yes" | "on" => Ok(true),
+        "false" | "no" | "off" => Ok(false),
+        _ => Err(format!(
+            "line {}: expected true or false, got `{}`",
+            number + 1,
+            value
+        )),
+    }
+}
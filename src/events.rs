@@ -0,0 +1,85 @@
+use crate::preview::PreviewContent;
+use crossterm::event::{self, Event};
+use std::io;
+use std::process::ExitStatus;
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::time::{Duration, Instant};
+
+/// How often [`AppEvent::Tick`] fires, drives spinners and debouncing.
+const TICK_RATE: Duration = Duration::from_millis(100);
+
+/// Everything the main loop reacts to.
+pub enum AppEvent {
+    /// A key press, resize or other terminal event.
+    Input(Event),
+    Tick,
+    /// Something changed in a watched directory.
+    FsChange,
+    /// A background command from the `:` prompt finished.
+    JobFinished {
+        id: usize,
+        status: io::Result<ExitStatus>,
+    },
+    /// A detached program exited.
+    ProcessExited {
+        command: String,
+        status: io::Result<ExitStatus>,
+    },
+    /// The preview for `path` has been loaded in the background.
+    PreviewReady {
+        path: String,
+        content: PreviewContent,
+    },
+}
+
+/// The channel every part of cb-ls sends its [`AppEvent`]s through.
+pub struct Events {
+    sender: Sender<AppEvent>,
+    receiver: Receiver<AppEvent>,
+    last_tick: Instant,
+}
+
+impl Default for Events {
+    fn default() -> Self {
+        let (sender, receiver) = mpsc::channel();
+        Self {
+            sender,
+            receiver,
+            last_tick: Instant::now(),
+        }
+    }
+}
+
+impl Events {
+    pub fn sender(&self) -> Sender<AppEvent> {
+        self.sender.clone()
+    }
+
+    /// Waits for the next event, which is at most a tick away.
+    ///
+    /// Terminal input is polled here on the main thread rather than from a
+    /// reader thread, so nothing steals keys from a program running in the
+    /// foreground while the UI is suspended.
+    pub fn next(&mut self) -> io::Result<AppEvent> {
+        loop {
+            // input and ticks come first, a chatty command filling the
+            // channel must not keep ^c from being read
+            if event::poll(Duration::ZERO)? {
+                return Ok(AppEvent::Input(event::read()?));
+            }
+
+            if self.last_tick.elapsed() >= TICK_RATE {
+                self.last_tick = Instant::now();
+                return Ok(AppEvent::Tick);
+            }
+
+            if let Ok(event) = self.receiver.try_recv() {
+                return Ok(event);
+            }
+
+            let until_tick = TICK_RATE.saturating_sub(self.last_tick.elapsed());
+            // wake up every now and then for events from other threads
+            event::poll(until_tick.min(Duration::from_millis(20)))?;
+        }
+    }
+}
@@ -0,0 +1,404 @@
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Once the ranks add up to more than this they all shrink, and directories
+/// that fall below a rank of 1 are forgotten.
+const MAX_AGE: f64 = 10000.0;
+
+const HOUR: u64 = 60 * 60;
+const DAY: u64 = 24 * HOUR;
+const WEEK: u64 = 7 * DAY;
+
+pub struct Entry {
+    pub path: PathBuf,
+    /// Grows by one with every visit.
+    pub rank: f64,
+    /// Seconds since the epoch.
+    pub last_access: u64,
+}
+
+/// How often and how lately directories were visited, like zoxide.
+///
+/// Kept in `$XDG_DATA_HOME/cb-ls/frecency`, one `path<TAB>rank<TAB>time`
+/// per line.
+#[derive(Default)]
+pub struct Frecency {
+    pub entries: Vec<Entry>,
+}
+
+impl Frecency {
+    pub fn default_path() -> Option<PathBuf> {
+        Some(crate::config::data_dir()?.join("frecency"))
+    }
+
+    /// Reads the database, a missing file is an empty one and broken lines are skipped.
+    pub fn load(path: &Path) -> Frecency {
+        let mut frecency = Frecency::default();
+        let Ok(content) = fs::read_to_string(path) else {
+            return frecency;
+        };
+
+        for line in content.lines() {
+            let mut fields = line.rsplitn(3, '\t');
+            let (Some(last_access), Some(rank), Some(path)) =
+                (fields.next(), fields.next(), fields.next())
+            else {
+                continue;
+            };
+            if let (Ok(rank), Ok(last_access)) = (rank.parse(), last_access.parse()) {
+                frecency.add(Path::new(path), rank, last_access);
+            }
+        }
+        frecency
+    }
+
+    pub fn save(&self, path: &Path) -> io::Result<()> {
+        crate::config::write_data_file(
+            path,
+            self.entries.iter().map(|entry| {
+                format!(
+                    "{}\t{}\t{}",
+                    entry.path.to_string_lossy(),
+                    entry.rank,
+                    entry.last_access
+                )
+            }),
+        )
+    }
+
+    pub fn visit(&mut self, dir: &Path) {
+        self.add(dir, 1.0, now());
+        self.age();
+    }
+
+    /// Adds `rank` to `dir`, e.g. from a visit or an imported database.
+    fn add(&mut self, dir: &Path, rank: f64, last_access: u64) {
+        match self.entries.iter_mut().find(|entry| entry.path == dir) {
+            Some(entry) => {
+                entry.rank += rank;
+                entry.last_access = entry.last_access.max(last_access);
+            }
+            None => self.entries.push(Entry {
+                path: dir.to_path_buf(),
+                rank,
+                last_access,
+            }),
+        }
+    }
+
+    fn age(&mut self) {
+        let total: f64 = self.entries.iter().map(|entry| entry.rank).sum();
+        if total <= MAX_AGE {
+            return;
+        }
+
+        let factor = 0.9 * MAX_AGE / total;
+        for entry in &mut self.entries {
+            entry.rank *= factor;
+        }
+        self.entries.retain(|entry| entry.rank >= 1.0);
+    }
+
+    /// Existing directories other than `current` matching `query`, best first.
+    ///
+    /// Like zoxide, every word of the query has to appear in the path in
+    /// order and the last one in the directory's own name. If nothing
+    /// matches, the query is matched fuzzily against the name instead.
+    pub fn query(&self, query: &str, current: &Path) -> Vec<PathBuf> {
+        let keywords: Vec<String> = query.split_whitespace().map(str::to_lowercase).collect();
+        let fuzzy: String = keywords.concat();
+        let now = now();
+
+        let mut found: Vec<&Entry> = self
+            .entries
+            .iter()
+            .filter(|entry| matches(&entry.path, &keywords))
+            .collect();
+        if found.is_empty() {
+            found = self
+                .entries
+                .iter()
+                .filter(|entry| fuzzy_matches(&entry.path, &fuzzy))
+                .collect();
+        }
+
+        found.retain(|entry| entry.path != current && entry.path.is_dir());
+        found.sort_by(|a, b| score(b, now).total_cmp(&score(a, now)));
+        found.into_iter().map(|entry| entry.path.clone()).collect()
+    }
+
+    /// Merges a z (or z.lua, zsh-z) database of `path|rank|time` lines,
+    /// returns how many directories it had.
+    pub fn import_z(&mut self, content: &str) -> usize {
+        let mut count = 0;
+        for line in content.lines() {
+            let mut fields = line.rsplitn(3, '|');
+            let (Some(last_access), Some(rank), Some(path)) =
+                (fields.next(), fields.next(), fields.next())
+            else {
+                continue;
+            };
+            if let (Ok(rank), Ok(last_access)) = (rank.parse(), last_access.parse()) {
+                self.add(Path::new(path), rank, last_access);
+                count += 1;
+            }
+        }
+        self.age();
+        count
+    }
+
+    /// Merges what `zoxide query --list --score` prints, returns how many directories it had.
+    pub fn import_zoxide(&mut self) -> Result<usize, String> {
+        let output = Command::new("zoxide")
+            .args(["query", "--list", "--score"])
+            .output()
+            .map_err(|err| format!("zoxide: {}", err))?;
+        if !output.status.success() {
+            return Err(format!(
+                "zoxide: {}",
+                String::from_utf8_lossy(&output.stderr).trim()
+            ));
+        }
+
+        Ok(self.merge_zoxide(&String::from_utf8_lossy(&output.stdout), now()))
+    }
+
+    /// Merges `rank path` lines as zoxide lists them, all visited at `now`
+    /// since zoxide doesn't list access times.
+    fn merge_zoxide(&mut self, list: &str, now: u64) -> usize {
+        let mut count = 0;
+        for line in list.lines() {
+            let Some((rank, path)) = line.trim_start().split_once(' ') else {
+                continue;
+            };
+            if let Ok(rank) = rank.parse() {
+                self.add(Path::new(path.trim_start()), rank, now);
+                count += 1;
+            }
+        }
+        self.age();
+        count
+    }
+}
+
+fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|duration| duration.as_secs())
+        .unwrap_or(0)
+}
+
+/// Rank weighted by how long ago the last visit was, the way zoxide does it.
+fn score(entry: &Entry, now: u64) -> f64 {
+    let age = now.saturating_sub(entry.last_access);
+    if age < HOUR {
+        entry.rank * 4.0
+    } else if age < DAY {
+        entry.rank * 2.0
+    } else if age < WEEK {
+        entry.rank / 2.0
+    } else {
+        entry.rank / 4.0
+    }
+}
+
+fn matches(path: &Path, keywords: &[String]) -> bool {
+    let path = path.to_string_lossy().to_lowercase();
+    let name_start = path.rfind('/').map_or(0, |index| index + 1);
+
+    let mut rest = 0;
+    for (i, keyword) in keywords.iter().enumerate() {
+        let last = i + 1 == keywords.len();
+        // the last keyword may also appear earlier, what counts is the name
+        let found = if last {
+            path[rest..].rfind(keyword.as_str())
+        } else {
+            path[rest..].find(keyword.as_str())
+        };
+        let Some(found) = found else {
+            return false;
+        };
+        rest += found + keyword.len();
+
+        if last && rest <= name_start {
+            return false;
+        }
+    }
+    true
+}
+
+/// Whether the characters of `query` appear in order in the directory's name.
+fn fuzzy_matches(path: &Path, query: &str) -> bool {
+    let Some(name) = path.file_name() else {
+        return false;
+    };
+    let name = name.to_string_lossy().to_lowercase();
+
+    let mut chars = name.chars();
+    query.chars().all(|c| chars.any(|n| n == c))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn keywords(query: &str) -> Vec<String> {
+        query.split_whitespace().map(String::from).collect()
+    }
+
+    fn entry(path: &Path, rank: f64, last_access: u64) -> Entry {
+        Entry {
+            path: path.to_path_buf(),
+            rank,
+            last_access,
+        }
+    }
+
+    /// A fresh directory under the temp dir, removed again by the caller.
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("cb-ls-{}-{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    #[test]
+    fn keywords_match_in_order() {
+        let path = Path::new("/home/u/projects/cb-ls");
+        assert!(matches(path, &keywords("pro cb")));
+        assert!(matches(path, &keywords("home pro ls")));
+        assert!(!matches(path, &keywords("cb pro")));
+        assert!(!matches(path, &keywords("pro nope cb")));
+    }
+
+    #[test]
+    fn last_keyword_must_be_in_the_name() {
+        let path = Path::new("/home/u/projects/cb-ls");
+        assert!(matches(path, &keywords("cb")));
+        assert!(!matches(path, &keywords("projects")));
+        assert!(!matches(path, &keywords("cb projects")));
+        // an earlier occurrence doesn't count against it
+        assert!(matches(Path::new("/cb/x/cbar"), &keywords("cb")));
+    }
+
+    #[test]
+    fn fuzzy_matches_the_name_only() {
+        assert!(fuzzy_matches(Path::new("/a/cb-ls"), "cbl"));
+        assert!(!fuzzy_matches(Path::new("/a/cb-ls"), "lcb"));
+        assert!(!fuzzy_matches(Path::new("/projects/x"), "pro"));
+        assert!(!fuzzy_matches(Path::new("/"), "a"));
+    }
+
+    #[test]
+    fn aging_shrinks_ranks_and_forgets_rare_directories() {
+        let mut frecency = Frecency::default();
+        frecency.add(Path::new("/often"), MAX_AGE, 0);
+        frecency.add(Path::new("/rare"), 1.0, 0);
+        frecency.age();
+
+        let total: f64 = frecency.entries.iter().map(|entry| entry.rank).sum();
+        assert!(total <= 0.9 * MAX_AGE + 1e-6, "{}", total);
+        assert_eq!(frecency.entries.len(), 1);
+        assert_eq!(frecency.entries[0].path, Path::new("/often"));
+    }
+
+    #[test]
+    fn no_aging_below_the_limit() {
+        let mut frecency = Frecency::default();
+        frecency.add(Path::new("/a"), 1.0, 5);
+        frecency.add(Path::new("/a"), 2.0, 3);
+        frecency.age();
+        assert_eq!(frecency.entries.len(), 1);
+        assert_eq!(frecency.entries[0].rank, 3.0);
+        assert_eq!(frecency.entries[0].last_access, 5);
+    }
+
+    #[test]
+    fn recent_visits_score_higher() {
+        let now = 100 * WEEK;
+        let old = entry(Path::new("/old"), 10.0, now - 2 * WEEK);
+        let new = entry(Path::new("/new"), 3.0, now - 60);
+        assert!(score(&new, now) > score(&old, now));
+        assert_eq!(
+            score(&entry(Path::new("/day"), 1.0, now - 2 * HOUR), now),
+            2.0
+        );
+        assert_eq!(
+            score(&entry(Path::new("/week"), 1.0, now - 2 * DAY), now),
+            0.5
+        );
+    }
+
+    #[test]
+    fn query_skips_the_current_and_missing_directories() {
+        let root = temp_dir("query");
+        let projects = root.join("projects");
+        let cb_ls = projects.join("cb-ls");
+        let cb_old = projects.join("cb-old");
+        fs::create_dir_all(&cb_ls).unwrap();
+        fs::create_dir_all(&cb_old).unwrap();
+
+        let mut frecency = Frecency::default();
+        frecency.add(&cb_ls, 1.0, now());
+        frecency.add(&cb_old, 10.0, 0);
+        frecency.add(&projects.join("cb-gone"), 1000.0, now());
+
+        assert_eq!(
+            frecency.query("pro cb", &root),
+            [cb_ls.clone(), cb_old.clone()]
+        );
+        assert_eq!(frecency.query("pro cb", &cb_ls), [cb_old]);
+        // nothing matches the keywords, so the names are matched fuzzily
+        assert_eq!(frecency.query("cbs", &root), [cb_ls]);
+
+        fs::remove_dir_all(&root).unwrap();
+    }
+
+    #[test]
+    fn imports_z_databases() {
+        let mut frecency = Frecency::default();
+        frecency.add(Path::new("/a"), 1.0, 50);
+        let count = frecency.import_z("/a|10|100\n/b|x|1\nbroken\n/with|pipe|2.5|7\n");
+
+        assert_eq!(count, 2);
+        assert_eq!(frecency.entries.len(), 2);
+        assert_eq!(frecency.entries[0].rank, 11.0);
+        assert_eq!(frecency.entries[0].last_access, 100);
+        assert_eq!(frecency.entries[1].path, Path::new("/with|pipe"));
+        assert_eq!(frecency.entries[1].rank, 2.5);
+    }
+
+    #[test]
+    fn imports_zoxide_lists() {
+        let mut frecency = Frecency::default();
+        let count = frecency.merge_zoxide("  12.5 /home/u/a\n   3 /with  spaces\nbad\nx /c\n", 9);
+
+        assert_eq!(count, 2);
+        assert_eq!(frecency.entries[0].path, Path::new("/home/u/a"));
+        assert_eq!(frecency.entries[0].rank, 12.5);
+        assert_eq!(frecency.entries[0].last_access, 9);
+        assert_eq!(frecency.entries[1].path, Path::new("/with  spaces"));
+    }
+
+    #[test]
+    fn saves_and_loads() {
+        let dir = temp_dir("frecency");
+        let file = dir.join("frecency");
+
+        let mut frecency = Frecency::default();
+        frecency.add(Path::new("/a b/c\td"), 2.5, 10);
+        frecency.add(Path::new("/e"), 1.0, 20);
+        frecency.save(&file).unwrap();
+
+        let loaded = Frecency::load(&file);
+        assert_eq!(loaded.entries.len(), 2);
+        assert_eq!(loaded.entries[0].path, Path::new("/a b/c\td"));
+        assert_eq!(loaded.entries[0].rank, 2.5);
+        assert_eq!(loaded.entries[1].last_access, 20);
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
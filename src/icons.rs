@@ -0,0 +1,113 @@
+use crate::ls_colors::EntryKind;
+use std::path::Path;
+
+/// Which glyphs to draw in front of entry names.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum IconSet {
+    /// Needs a patched Nerd Font in the terminal.
+    Nerd,
+    /// Plain ASCII markers that work in any terminal.
+    Ascii,
+    Off,
+}
+
+impl Default for IconSet {
+    fn default() -> Self {
+        match std::env::var("CBLS_ICONS") {
+            Ok(value) => IconSet::from_name(&value).unwrap_or(IconSet::Nerd),
+            Err(_) => IconSet::Nerd,
+        }
+    }
+}
+
+impl IconSet {
+    pub fn from_name(name: &str) -> Option<IconSet> {
+        match name.trim().to_lowercase().as_str() {
+            "nerd" | "nerdfont" => Some(IconSet::Nerd),
+            "ascii" => Some(IconSet::Ascii),
+            "off" | "none" => Some(IconSet::Off),
+            _ => None,
+        }
+    }
+
+    /// Icon plus a trailing space, or an empty string when icons are off.
+    pub fn icon_for(&self, path: &Path, kind: EntryKind) -> String {
+        let icon = match self {
+            IconSet::Off => return String::new(),
+            IconSet::Ascii => ascii_icon(kind),
+            IconSet::Nerd => nerd_icon(path, kind),
+        };
+        format!("{} ", icon)
+    }
+}
+
+fn ascii_icon(kind: EntryKind) -> &'static str {
+    match kind {
+        EntryKind::Dir => "d",
+        EntryKind::Symlink => "l",
+        EntryKind::BrokenSymlink => "!",
+        EntryKind::Executable => "*",
+        EntryKind::Fifo => "|",
+        EntryKind::Socket => "=",
+        EntryKind::BlockDevice => "b",
+        EntryKind::CharDevice => "c",
+        EntryKind::File => "-",
+        EntryKind::Missing => "?",
+    }
+}
+
+fn nerd_icon(path: &Path, kind: EntryKind) -> &'static str {
+    match kind {
+        EntryKind::Dir => return "\u{f07b}",
+        EntryKind::Symlink => return "\u{f0c1}",
+        EntryKind::BrokenSymlink => return "\u{f127}",
+        EntryKind::Fifo => return "\u{f07e}",
+        EntryKind::Socket => return "\u{f1e6}",
+        EntryKind::BlockDevice | EntryKind::CharDevice => return "\u{f0a0}",
+        EntryKind::Missing => return "\u{f128}",
+        EntryKind::Executable | EntryKind::File => {}
+    }
+
+    let name = path
+        .file_name()
+        .map(|name| name.to_string_lossy().to_lowercase())
+        .unwrap_or_default();
+
+    match name.as_str() {
+        "makefile" | "cmakelists.txt" => return "\u{e779}",
+        "dockerfile" => return "\u{f308}",
+        "license" | "license.md" => return "\u{f0219}",
+        ".gitignore" | ".gitmodules" | ".gitattributes" => return "\u{f1d3}",
+        "cargo.toml" | "cargo.lock" => return "\u{e7a8}",
+        _ => {}
+    }
+
+    let extension = name.rsplit_once('.').map(|(_, ext)| ext).unwrap_or("");
+    match extension {
+        "rs" => "\u{e7a8}",
+        "py" => "\u{e606}",
+        "js" | "mjs" | "cjs" => "\u{e74e}",
+        "ts" | "tsx" => "\u{e628}",
+        "go" => "\u{e626}",
+        "c" | "h" => "\u{e61e}",
+        "cpp" | "cc" | "hpp" => "\u{e61d}",
+        "java" | "jar" => "\u{e738}",
+        "lua" => "\u{e620}",
+        "sh" | "bash" | "zsh" | "fish" => "\u{f489}",
+        "html" | "htm" => "\u{e736}",
+        "css" | "scss" => "\u{e749}",
+        "json" | "toml" | "yaml" | "yml" | "ini" | "conf" => "\u{e615}",
+        "md" | "markdown" => "\u{e73e}",
+        "txt" | "log" => "\u{f15c}",
+        "pdf" => "\u{f1c1}",
+        "png" | "jpg" | "jpeg" | "gif" | "bmp" | "webp" | "svg" | "ico" => "\u{f1c5}",
+        "mp3" | "flac" | "ogg" | "wav" | "m4a" => "\u{f1c7}",
+        "mp4" | "mkv" | "webm" | "avi" | "mov" => "\u{f1c8}",
+        "zip" | "tar" | "gz" | "xz" | "bz2" | "zst" | "7z" | "rar" => "\u{f1c6}",
+        "doc" | "docx" | "odt" => "\u{f1c2}",
+        "xls" | "xlsx" | "ods" | "csv" => "\u{f1c3}",
+        "ppt" | "pptx" | "odp" => "\u{f1c4}",
+        _ if kind == EntryKind::Executable => "\u{f489}",
+        _ => "\u{f15b}",
+    }
+}
@@ -0,0 +1,245 @@
+use crate::events::AppEvent;
+use crate::tui::{self, Tui};
+use std::collections::BTreeMap;
+use std::fs::OpenOptions;
+use std::io::{self, BufRead, BufReader, IsTerminal, Read};
+use std::os::unix::process::CommandExt;
+use std::path::Path;
+use std::process::{Command, ExitStatus, Stdio};
+use std::sync::mpsc::Sender;
+use std::sync::{Arc, Mutex};
+use std::thread;
+
+/// How many bytes of a job's output are kept, older lines are dropped.
+const MAX_OUTPUT: usize = 1 << 20;
+
+/// A command from the `:` prompt running in the background.
+pub struct Job {
+    pub command: String,
+    /// Filled in by the threads reading its stdout and stderr.
+    pub output: Arc<Mutex<JobOutput>>,
+    /// Open the output pane when it finishes, false for commands ending in `&`.
+    pub show_output: bool,
+    /// Its process group, killed by [`Launcher::cancel`].
+    pgid: i32,
+}
+
+impl Job {
+    /// The line it printed last, for the job line below the list.
+    pub fn last_line(&self) -> Option<String> {
+        let output = self.output.lock().unwrap();
+        output.text.lines().next_back().map(String::from)
+    }
+}
+
+/// What a job printed, stdout and stderr in the order the lines came in.
+#[derive(Default)]
+pub struct JobOutput {
+    /// At most [`MAX_OUTPUT`] bytes, the newest lines.
+    pub text: String,
+    /// Whether the oldest lines were dropped.
+    pub truncated: bool,
+}
+
+impl JobOutput {
+    /// Appends a line. Past [`MAX_OUTPUT`] the older half is dropped, so
+    /// long outputs aren't shifted on every line.
+    fn push_line(&mut self, line: &str) {
+        self.text.push_str(line);
+        self.text.push('\n');
+        if self.text.len() <= MAX_OUTPUT {
+            return;
+        }
+
+        let keep_from = self.text.len() - MAX_OUTPUT / 2;
+        let cut = self.text.as_bytes()[keep_from..]
+            .iter()
+            .position(|&byte| byte == b'\n')
+            .map_or(self.text.len(), |end| keep_from + end + 1);
+        self.text.drain(..cut);
+        self.truncated = true;
+    }
+}
+
+/// Starts external programs without blocking the UI.
+///
+/// Exit statuses and output come back as [`AppEvent`]s.
+pub struct Launcher {
+    sender: Sender<AppEvent>,
+    /// A terminal program to run once the event loop has the terminal at hand,
+    /// with the label to log its exit status under.
+    pub foreground: Option<(String, Command)>,
+    pub jobs: BTreeMap<usize, Job>,
+    next_job: usize,
+}
+
+impl Launcher {
+    pub fn new(sender: Sender<AppEvent>) -> Launcher {
+        Self {
+            sender,
+            foreground: None,
+            jobs: BTreeMap::new(),
+            next_job: 0,
+        }
+    }
+
+    /// Starts a GUI program in its own session with stdio going nowhere, so
+    /// it neither draws over the UI nor dies with cb-ls.
+    pub fn spawn_detached(&mut self, command: &str, dir: &Path) -> io::Result<()> {
+        let mut process = shell_command(command, dir);
+        process
+            .stdin(Stdio::null())
+            .stdout(Stdio::null())
+            .stderr(Stdio::null());
+
+        // SAFETY: setsid is async-signal-safe and touches no memory of ours.
+        unsafe {
+            process.pre_exec(|| {
+                libc::setsid();
+                Ok(())
+            });
+        }
+
+        let mut child = process.spawn()?;
+
+        let sender = self.sender.clone();
+        let command = command.to_string();
+        thread::spawn(move || {
+            let status = child.wait();
+            let _ = sender.send(AppEvent::ProcessExited { command, status });
+        });
+        Ok(())
+    }
+
+    /// Runs `command` in the background, collecting its output in the
+    /// [`Job`] until [`AppEvent::JobFinished`].
+    pub fn spawn_captured(
+        &mut self,
+        command: &str,
+        dir: &Path,
+        show_output: bool,
+    ) -> io::Result<()> {
+        // a group of its own, so cancelling also stops what `sh` started
+        let mut child = shell_command(command, dir)
+            .stdin(Stdio::null())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .process_group(0)
+            .spawn()?;
+
+        let id = self.next_job;
+        self.next_job += 1;
+        let output = Arc::new(Mutex::new(JobOutput::default()));
+        self.jobs.insert(
+            id,
+            Job {
+                command: command.to_string(),
+                output: Arc::clone(&output),
+                show_output,
+                pgid: child.id() as i32,
+            },
+        );
+
+        let stdout = child.stdout.take();
+        let stderr = child.stderr.take();
+        let sender = self.sender.clone();
+        // the lines are collected rather than sent one by one, a chatty
+        // command would flood the event channel
+        thread::spawn(move || {
+            let stdout_output = Arc::clone(&output);
+            let stdout_reader = thread::spawn(move || collect_lines(stdout, &stdout_output));
+            collect_lines(stderr, &output);
+            let _ = stdout_reader.join();
+
+            let status = child.wait();
+            let _ = sender.send(AppEvent::JobFinished { id, status });
+        });
+        Ok(())
+    }
+
+    /// Stops the newest running job, returning its command. It still
+    /// reports back with [`AppEvent::JobFinished`].
+    pub fn cancel(&mut self) -> Option<String> {
+        let job = self.jobs.values().next_back()?;
+        unsafe {
+            libc::kill(-job.pgid, libc::SIGTERM);
+        }
+        Some(job.command.clone())
+    }
+}
+
+fn collect_lines(pipe: Option<impl Read>, output: &Mutex<JobOutput>) {
+    let Some(pipe) = pipe else {
+        return;
+    };
+    for line in BufReader::new(pipe).lines().map_while(Result::ok) {
+        output.lock().unwrap().push_line(&line);
+    }
+}
+
+/// `sh -c command`, started in `dir`.
+pub fn shell_command(command: &str, dir: &Path) -> Command {
+    let mut process = Command::new("sh");
+    process.arg("-c").arg(command).current_dir(dir);
+    process
+}
+
+/// The user's `$SHELL` in `dir`, with `CBLS_SELECTION` holding the selected
+/// paths (one per line) and `CBLS_LEVEL` counting how deeply cb-ls is nested.
+pub fn subshell(dir: &Path, selection: &[String]) -> Command {
+    let shell = std::env::var("SHELL").unwrap_or_else(|_| String::from("/bin/sh"));
+    let level: u32 = std::env::var("CBLS_LEVEL")
+        .ok()
+        .and_then(|level| level.parse().ok())
+        .unwrap_or(0);
+
+    let mut process = Command::new(shell);
+    process
+        .current_dir(dir)
+        .env("CBLS_SELECTION", selection.join("\n"))
+        .env("CBLS_LEVEL", (level + 1).to_string());
+    process
+}
+
+/// Leaves the UI, runs `process` attached to the terminal and comes back.
+pub fn run_foreground(terminal: &mut Tui, mut process: Command) -> io::Result<ExitStatus> {
+    tui::suspend()?;
+
+    // stdout may be taken by --print-last-dir or --pick, talk to the tty directly
+    if !io::stdout().is_terminal()
+        && let Ok(tty) = OpenOptions::new().read(true).write(true).open("/dev/tty")
+    {
+        process
+            .stdin(tty.try_clone()?)
+            .stdout(tty.try_clone()?)
+            .stderr(tty);
+    }
+
+    let status = process.status();
+    tui::resume(terminal)?;
+    status
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn output_keeps_the_newest_lines() {
+        let mut output = JobOutput::default();
+        output.push_line("first");
+        assert_eq!(output.text, "first\n");
+        assert!(!output.truncated);
+
+        let line = "y".repeat(99);
+        for _ in 0..MAX_OUTPUT / 100 + 1 {
+            output.push_line(&line);
+        }
+        output.push_line("last");
+        assert!(output.truncated);
+        assert!(output.text.len() <= MAX_OUTPUT);
+        assert!(!output.text.contains("first"));
+        assert!(output.text.starts_with(&line));
+        assert!(output.text.ends_with("last\n"));
+    }
+}
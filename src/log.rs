@@ -0,0 +1,98 @@
+use chrono::{DateTime, Local};
+use std::collections::VecDeque;
+use std::time::{Duration, Instant};
+
+/// Older messages are dropped once the log holds this many.
+const MAX_MESSAGES: usize = 500;
+/// How long a warning or error stays in the status bar.
+const FLASH_TIME: Duration = Duration::from_secs(5);
+
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Debug)]
+pub enum Level {
+    Info,
+    Warning,
+    Error,
+}
+
+impl Level {
+    pub fn name(self) -> &'static str {
+        match self {
+            Level::Info => "info",
+            Level::Warning => "warn",
+            Level::Error => "error",
+        }
+    }
+}
+
+pub struct Message {
+    pub time: DateTime<Local>,
+    pub level: Level,
+    /// What cb-ls was doing, e.g. `copy` or the command that ran.
+    pub action: String,
+    pub text: String,
+}
+
+/// Everything cb-ls has to report: finished commands, failed file
+/// operations, bad input. Shown in the log panel (`l`), warnings and errors
+/// also flash in the status bar.
+#[derive(Default)]
+pub struct Log {
+    messages: VecDeque<Message>,
+    /// Until when the latest warning or error is shown in the status bar.
+    flash_until: Option<Instant>,
+}
+
+impl Log {
+    pub fn info(&mut self, action: &str, text: impl Into<String>) {
+        self.push(Level::Info, action, text.into());
+    }
+
+    pub fn warn(&mut self, action: &str, text: impl Into<String>) {
+        self.push(Level::Warning, action, text.into());
+    }
+
+    pub fn error(&mut self, action: &str, text: impl Into<String>) {
+        self.push(Level::Error, action, text.into());
+    }
+
+    fn push(&mut self, level: Level, action: &str, text: String) {
+        if self.messages.len() == MAX_MESSAGES {
+            self.messages.pop_front();
+        }
+        self.messages.push_back(Message {
+            time: Local::now(),
+            level,
+            action: action.to_string(),
+            text: text.trim_end().to_string(),
+        });
+
+        if level >= Level::Warning {
+            self.flash_until = Some(Instant::now() + FLASH_TIME);
+        }
+    }
+
+    /// Oldest first.
+    pub fn messages(&self) -> impl DoubleEndedIterator<Item = &Message> + ExactSizeIterator {
+        self.messages.iter()
+    }
+
+    /// The warning or error to show in the status bar, if it is recent enough.
+    pub fn flash(&self) -> Option<&Message> {
+        self.flash_until?;
+        self.messages
+            .iter()
+            .rev()
+            .find(|message| message.level >= Level::Warning)
+    }
+
+    /// Clears the flash once its time is up, returns whether it did.
+    pub fn expire_flash(&mut self) -> bool {
+        match self.flash_until {
+            Some(until) if Instant::now() >= until => {
+                self.flash_until = None;
+                true
+            }
+            _ => false,
+        }
+    }
+}
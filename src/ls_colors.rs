@@ -0,0 +1,206 @@
+use ratatui::style::{Color, Modifier, Style};
+use std::collections::HashMap;
+use std::fs;
+use std::os::unix::fs::{FileTypeExt, PermissionsExt};
+use std::path::Path;
+
+/// What kind of filesystem entry a path is, as far as colouring and icons care.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum EntryKind {
+    Dir,
+    Symlink,
+    BrokenSymlink,
+    Executable,
+    Fifo,
+    Socket,
+    BlockDevice,
+    CharDevice,
+    File,
+    Missing,
+}
+
+impl EntryKind {
+    pub fn of(path: &Path) -> EntryKind {
+        let metadata = match fs::symlink_metadata(path) {
+            Ok(metadata) => metadata,
+            Err(_) => return EntryKind::Missing,
+        };
+        let file_type = metadata.file_type();
+
+        if file_type.is_symlink() {
+            if fs::metadata(path).is_ok() {
+                EntryKind::Symlink
+            } else {
+                EntryKind::BrokenSymlink
+            }
+        } else if file_type.is_dir() {
+            EntryKind::Dir
+        } else if file_type.is_fifo() {
+            EntryKind::Fifo
+        } else if file_type.is_socket() {
+            EntryKind::Socket
+        } else if file_type.is_block_device() {
+            EntryKind::BlockDevice
+        } else if file_type.is_char_device() {
+            EntryKind::CharDevice
+        } else if metadata.permissions().mode() & 0o111 != 0 {
+            EntryKind::Executable
+        } else {
+            EntryKind::File
+        }
+    }
+
+    /// The two letter key `LS_COLORS` uses for this kind.
+    fn key(self) -> &'static str {
+        match self {
+            EntryKind::Dir => "di",
+            EntryKind::Symlink => "ln",
+            EntryKind::BrokenSymlink => "or",
+            EntryKind::Executable => "ex",
+            EntryKind::Fifo => "pi",
+            EntryKind::Socket => "so",
+            EntryKind::BlockDevice => "bd",
+            EntryKind::CharDevice => "cd",
+            EntryKind::File => "fi",
+            EntryKind::Missing => "mi",
+        }
+    }
+}
+
+/// Styles parsed from the `LS_COLORS` environment variable.
+pub struct LsColors {
+    kinds: HashMap<String, Style>,
+    extensions: HashMap<String, Style>,
+    link_as_target: bool,
+}
+
+impl Default for LsColors {
+    fn default() -> Self {
+        match std::env::var("LS_COLORS") {
+            Ok(value) if !value.is_empty() => LsColors::parse(&value),
+            // same colours GNU dircolors uses when nothing is configured
+            _ => LsColors::parse(
+                "di=01;34:ln=01;36:or=40;31;01:mi=00:pi=40;33:so=01;35:bd=40;33;01:cd=40;33;01:ex=01;32",
+            ),
+        }
+    }
+}
+
+impl LsColors {
+    pub fn parse(value: &str) -> Self {
+        let mut kinds = HashMap::new();
+        let mut extensions = HashMap::new();
+        let mut link_as_target = false;
+
+        for entry in value.split(':') {
+            let Some((key, codes)) = entry.split_once('=') else {
+                continue;
+            };
+            if key == "ln" && codes == "target" {
+                link_as_target = true;
+                continue;
+            }
+            let style = parse_sgr(codes);
+
+            if let Some(extension) = key.strip_prefix("*.") {
+                extensions.insert(extension.to_lowercase(), style);
+            } else if let Some(name) = key.strip_prefix('*') {
+                // "*README" style entries match the whole file name
+                extensions.insert(name.to_lowercase(), style);
+            } else {
+                kinds.insert(key.to_string(), style);
+            }
+        }
+
+        Self {
+            kinds,
+            extensions,
+            link_as_target,
+        }
+    }
+
+    pub fn style_for(&self, path: &Path, kind: EntryKind) -> Style {
+        // "ln=target" colours links like whatever they point to
+        let kind = if kind == EntryKind::Symlink && self.link_as_target {
+            EntryKind::of(&fs::canonicalize(path).unwrap_or_default())
+        } else {
+            kind
+        };
+
+        if kind != EntryKind::File
+            && let Some(style) = self.kinds.get(kind.key())
+        {
+            return *style;
+        }
+
+        if let Some(name) = path.file_name() {
+            let name = name.to_string_lossy().to_lowercase();
+            if let Some(style) = self.extensions.get(&name) {
+                return *style;
+            }
+            if let Some((_, extension)) = name.rsplit_once('.')
+                && let Some(style) = self.extensions.get(extension)
+            {
+                return *style;
+            }
+        }
+
+        self.kinds.get("fi").copied().unwrap_or_default()
+    }
+}
+
+/// Turns an SGR sequence like `01;38;5;208` into a ratatui style.
+fn parse_sgr(codes: &str) -> Style {
+    let mut style = Style::default();
+    let codes: Vec<u8> = codes
+        .split(';')
+        .filter_map(|code| code.parse().ok())
+        .collect();
+
+    let mut i = 0;
+    while i < codes.len() {
+        match codes[i] {
+            0 => style = Style::default(),
+            1 => style = style.add_modifier(Modifier::BOLD),
+            2 => style = style.add_modifier(Modifier::DIM),
+            3 => style = style.add_modifier(Modifier::ITALIC),
+            4 => style = style.add_modifier(Modifier::UNDERLINED),
+            5 => style = style.add_modifier(Modifier::SLOW_BLINK),
+            7 => style = style.add_modifier(Modifier::REVERSED),
+            9 => style = style.add_modifier(Modifier::CROSSED_OUT),
+            code @ 30..=37 => style = style.fg(Color::Indexed(code - 30)),
+            code @ 40..=47 => style = style.bg(Color::Indexed(code - 40)),
+            code @ 90..=97 => style = style.fg(Color::Indexed(code - 90 + 8)),
+            code @ 100..=107 => style = style.bg(Color::Indexed(code - 100 + 8)),
+            code @ (38 | 48) => {
+                let color = match codes.get(i + 1) {
+                    Some(5) => {
+                        let color = codes.get(i + 2).map(|&n| Color::Indexed(n));
+                        i += 2;
+                        color
+                    }
+                    Some(2) => {
+                        let color = match (codes.get(i + 2), codes.get(i + 3), codes.get(i + 4)) {
+                            (Some(&r), Some(&g), Some(&b)) => Some(Color::Rgb(r, g, b)),
+                            _ => None,
+                        };
+                        i += 4;
+                        color
+                    }
+                    _ => None,
+                };
+                if let Some(color) = color {
+                    style = if code == 38 {
+                        style.fg(color)
+                    } else {
+                        style.bg(color)
+                    };
+                }
+            }
+            _ => {}
+        }
+        i += 1;
+    }
+
+    style
+}
@@ -1,35 +1,253 @@
+mod bookmarks;
+mod cli;
+mod clipboard;
+mod completion;
+mod config;
+mod disk;
+mod events;
+mod frecency;
+mod icons;
+mod launcher;
+mod log;
+mod ls_colors;
+mod opener;
+mod picker;
+mod preview;
+mod recent;
+mod selection;
+mod sort;
+mod system_clipboard;
+mod tui;
+mod watcher;
+
+use bookmarks::Bookmarks;
 use chrono::DateTime;
-use crossterm::event::{Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers};
-use crossterm::*;
-//use natord::compare;
+use clipboard::{ClipMode, Clipboard, PasteMode};
+use config::Config;
+use crossterm::event::{
+    Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers, MouseButton, MouseEvent, MouseEventKind,
+};
+use events::{AppEvent, Events};
+use frecency::Frecency;
+use icons::IconSet;
+use launcher::Launcher;
+use log::{Level, Log};
+use ls_colors::{EntryKind, LsColors};
+use opener::{LaunchMode, Opener};
+use picker::Picker;
+use preview::{Preview, PreviewContent};
 use ratatui::buffer::Buffer;
 use ratatui::layout::{Constraint, Direction, Layout, Rect};
-use ratatui::style::{Color, Style, Stylize};
+use ratatui::style::{Color, Modifier, Style, Stylize};
 use ratatui::text::{Line, Span, Text};
 use ratatui::widgets::{
-    Block, BorderType, Borders, List, ListItem, ListState, Padding, Paragraph, Widget, Wrap,
+    Block, BorderType, Borders, List, ListItem, ListState, Padding, Paragraph, Tabs, Widget, Wrap,
 };
 use ratatui::*;
+use recent::Recent;
+use selection::{NamePattern, VisualRange};
+use sort::SortMode;
+use std::collections::HashMap;
 use std::path::{Path, PathBuf};
-use std::process::{Command,};
-use std::time::UNIX_EPOCH;
+use std::process::{Command, ExitStatus, Output};
+use std::time::{Duration, Instant, UNIX_EPOCH};
 use std::{fs, io};
+use system_clipboard::CopyMethod;
+use watcher::FsWatcher;
 
 fn main() -> io::Result<()> {
-    let mut terminal = init();
-    let mut app = App::default();
+    let args = match cli::parse(std::env::args().skip(1)) {
+        Ok(args) => args,
+        Err(err) => {
+            eprintln!("cb-ls: {}\nTry 'cb-ls --help' for more information.", err);
+            std::process::exit(2);
+        }
+    };
+
+    if args.help {
+        print!("{}", cli::USAGE);
+        return Ok(());
+    }
+    if args.version {
+        println!("cb-ls {}", env!("CARGO_PKG_VERSION"));
+        return Ok(());
+    }
+    if args.import_z.is_some() || args.import_zoxide {
+        if let Err(err) = import(&args) {
+            eprintln!("cb-ls: {}", err);
+            std::process::exit(1);
+        }
+        return Ok(());
+    }
+
+    let mut app = match App::from_args(&args) {
+        Ok(app) => app,
+        Err(err) => {
+            eprintln!("cb-ls: {}", err);
+            std::process::exit(1);
+        }
+    };
+
+    let mut terminal = tui::init()?;
     let app_result = app.run(&mut terminal);
-    restore();
+    tui::restore();
+
+    if let Some(path) = Recent::default_path()
+        && let Err(err) = app.recent.save(&path)
+    {
+        eprintln!("cb-ls: cannot save {}: {}", path.display(), err);
+    }
+    if let Some(path) = Frecency::default_path()
+        && let Err(err) = app.frecency.save(&path)
+    {
+        eprintln!("cb-ls: cannot save {}: {}", path.display(), err);
+    }
+    if app.keep_selection
+        && let Some(path) = selection::default_path()
+        && let Err(err) = selection::save(&path, &app.notes.selected_items)
+    {
+        eprintln!("cb-ls: cannot save {}: {}", path.display(), err);
+    }
+
+    if app.cd_on_exit {
+        app.write_last_dir(&args)?;
+    }
+
+    if let Some(picker) = &app.picker {
+        if picker.picked.is_none() {
+            std::process::exit(1);
+        }
+        picker.write()?;
+    }
 
     app_result
 }
+
+/// `--import-z` and `--import-zoxide`: merges another jumper's database into ours.
+fn import(args: &cli::Args) -> Result<(), String> {
+    let path = Frecency::default_path().ok_or("cannot find the data directory, HOME is not set")?;
+    let mut frecency = Frecency::load(&path);
+
+    if let Some(file) = &args.import_z {
+        let content = fs::read_to_string(file)
+            .map_err(|err| format!("cannot read {}: {}", file.display(), err))?;
+        let count = frecency.import_z(&content);
+        println!("Imported {} directories from {}", count, file.display());
+    }
+    if args.import_zoxide {
+        let count = frecency.import_zoxide()?;
+        println!("Imported {} directories from zoxide", count);
+    }
+
+    frecency
+        .save(&path)
+        .map_err(|err| format!("cannot save {}: {}", path.display(), err))
+}
 pub struct App {
     exit: bool,
-    input: String,
+    /// The directory listing of the current tab.
     notes: FileList,
+    /// The other tabs, in order around the current one at index `tab`.
+    tabs: Vec<FileList>,
+    tab: usize,
+    /// The second listing, only in the dual-pane layout.
+    other_pane: Option<OtherPane>,
+    /// Ranger-style parent, current and child columns.
+    miller: bool,
     selected_widget: SelectedWidget,
-    error_output: Vec<String>,
+    log: Log,
+    /// The log panel, `l`, scrolled up from the newest message by `y`.
+    log_panel: Option<Scroll>,
     help: bool,
+    ls_colors: LsColors,
+    icons: IconSet,
+    cd_on_exit: bool,
+    picker: Option<Picker>,
+    openers: Vec<Opener>,
+    open_with: Option<OpenWith>,
+    recent: Recent,
+    /// The recent directories menu, `R`.
+    recent_menu: Option<ListState>,
+    bookmarks: Bookmarks,
+    bookmark_menu: Option<BookmarkMenu>,
+    frecency: Frecency,
+    jump_prompt: Option<JumpPrompt>,
+    goto_prompt: Option<GotoPrompt>,
+    /// The directory last recorded in `recent` and `frecency`.
+    visited: Option<PathBuf>,
+    clipboard: Option<Clipboard>,
+    /// Save the selection on exit, `keep_selection` in the config.
+    keep_selection: bool,
+    /// Visual mode, `v`, selecting a range of entries.
+    visual: Option<VisualRange>,
+    pattern_prompt: Option<PatternPrompt>,
+    /// How `Y` puts text on the system clipboard.
+    copy_method: CopyMethod,
+    /// `m` or `'` waiting for the key of a mark, or `Y` for what to copy.
+    pending_key: Option<char>,
+    launcher: Launcher,
+    command_prompt: CommandPrompt,
+    command_output: Option<CommandOutput>,
+    watcher: FsWatcher,
+    events: Events,
+    preview: Preview,
+    /// Frame of the spinner shown while background jobs run.
+    spinner: usize,
+    /// Something changed since the last draw.
+    dirty: bool,
+    /// Where the panes were drawn last, for mouse clicks.
+    areas: PaneAreas,
+    /// Entry and time of the last left click, to spot double clicks.
+    last_click: Option<(usize, Instant)>,
+}
+
+impl Default for App {
+    fn default() -> Self {
+        let events = Events::default();
+
+        Self {
+            exit: false,
+            notes: FileList::default(),
+            tabs: Vec::new(),
+            tab: 0,
+            other_pane: None,
+            miller: false,
+            selected_widget: SelectedWidget::default(),
+            log: Log::default(),
+            log_panel: None,
+            help: false,
+            ls_colors: LsColors::default(),
+            icons: IconSet::default(),
+            cd_on_exit: false,
+            picker: None,
+            openers: opener::default_openers(),
+            open_with: None,
+            recent: Recent::default(),
+            recent_menu: None,
+            bookmarks: Bookmarks::default(),
+            bookmark_menu: None,
+            frecency: Frecency::default(),
+            jump_prompt: None,
+            goto_prompt: None,
+            visited: None,
+            pending_key: None,
+            clipboard: None,
+            keep_selection: false,
+            visual: None,
+            pattern_prompt: None,
+            copy_method: CopyMethod::default(),
+            launcher: Launcher::new(events.sender()),
+            command_prompt: CommandPrompt::default(),
+            command_output: None,
+            watcher: FsWatcher::new(events.sender()),
+            events,
+            preview: Preview::default(),
+            spinner: 0,
+            dirty: true,
+            areas: PaneAreas::default(),
+            last_click: None,
+        }
+    }
 }
 
 pub struct FileList {
@@ -37,13 +255,41 @@ pub struct FileList {
     items: Vec<String>,
     selected_items: Vec<String>,
     state: ListState,
-    is_file: bool,
-    is_dir: bool,
     is_active: bool,
     create_folder: FolderCreation,
     creat_file: FileCreation,
+    show_hidden: bool,
+    sort: SortMode,
+    /// A hidden entry listed anyway, see [`FileList::reveal_name`].
+    revealed: Option<String>,
+    /// Name of the entry the cursor was on in directories left before.
+    cursors: HashMap<PathBuf, String>,
+    /// Directories to go back and forward to, like in a web browser.
+    back: Vec<PathBuf>,
+    forward: Vec<PathBuf>,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum Pane {
+    List,
+    Preview,
+    Info,
+    Selection,
+}
+
+#[derive(Default)]
+pub struct PaneAreas {
+    tabs: Rect,
+    list: Rect,
+    other_pane: Rect,
+    preview: Rect,
+    info: Rect,
+    selection: Rect,
+    /// Index of the first entry visible in the list.
+    list_offset: usize,
 }
 
+#[derive(Default)]
 pub struct SelectedWidget {
     file_info: FileInfo,
     file_preview: FilePreview,
@@ -51,259 +297,412 @@ pub struct SelectedWidget {
     file_selection: FileSelection,
 }
 
+#[derive(Default)]
 pub struct FilePreview {
     is_active: bool,
     scroll: Scroll,
 }
 
+#[derive(Default)]
 pub struct FileInfo {
     is_active: bool,
     scroll: Scroll,
 }
 
+#[derive(Default)]
 pub struct FileSelection {
     is_active: bool,
-    scroll: Scroll,
+    /// Index of the entry under the panel's cursor, in panel order.
+    cursor: usize,
+    state: ListState,
 }
 
+#[derive(Default)]
 pub struct Scroll {
     y: u16,
     x: u16,
 }
 
+#[derive(Default)]
 pub struct FolderCreation {
     is_active: bool,
     user_input: String,
 }
 
+#[derive(Default)]
 pub struct FileCreation {
     is_active: bool,
     user_input: String,
 }
 
-impl Default for App {
-    fn default() -> Self {
-        Self {
-            exit: false,
-            input: String::from(""),
-            notes: FileList::default(),
-            selected_widget: SelectedWidget::default(),
-            error_output: vec![],
-            help: false,
-        }
-    }
+/// The `:` prompt for running shell commands.
+#[derive(Default)]
+pub struct CommandPrompt {
+    is_active: bool,
+    user_input: String,
+}
+
+/// What the last command from the `:` prompt printed.
+pub struct CommandOutput {
+    command: String,
+    text: String,
+    scroll: Scroll,
+}
+
+/// The `z` prompt, jumping to frequently and recently visited directories.
+#[derive(Default)]
+pub struct JumpPrompt {
+    user_input: String,
+    matches: Vec<PathBuf>,
+    state: ListState,
+}
+
+/// The `+` and `-` prompts for a glob or `/regex/` to select or deselect.
+pub struct PatternPrompt {
+    user_input: String,
+    select: bool,
+}
+
+/// The `g` prompt for typing a path to go to.
+#[derive(Default)]
+pub struct GotoPrompt {
+    user_input: String,
+    /// Directories the input can be completed to, cycled through with Tab.
+    candidates: Vec<String>,
+    state: ListState,
+}
+
+/// The bookmark manager, `b`.
+#[derive(Default)]
+pub struct BookmarkMenu {
+    state: ListState,
+    /// The new name while renaming the bookmark under the cursor.
+    rename: Option<String>,
+}
+
+/// The inactive side of the dual-pane layout.
+pub struct OtherPane {
+    list: FileList,
+    /// Whether it is drawn on the right, i.e. the current listing is the left pane.
+    on_right: bool,
+}
+
+/// The "open with…" menu, listing every opener that matches `path`.
+pub struct OpenWith {
+    path: String,
+    openers: Vec<usize>,
+    state: ListState,
 }
 
 impl Default for FileList {
     fn default() -> Self {
-        let mut notes = Vec::new();
+        let path = std::env::current_dir().unwrap_or_else(|_| PathBuf::from("/")); // current directory
 
-        let path = std::env::current_dir().unwrap(); // current directory
-
-        for entry in fs::read_dir(&path).unwrap() {
-            let entry = entry.unwrap();
-            notes.push(entry.path().to_string_lossy().to_string());
-        }
+        let mut notes = FileList::new(path, false, SortMode::default());
+        notes.update();
+        notes
+    }
+}
 
-        Self {
+impl FileList {
+    /// A listing of `path` that hasn't been read yet.
+    fn new(path: PathBuf, show_hidden: bool, sort: SortMode) -> FileList {
+        FileList {
             path,
-            items: notes,
+            items: Vec::new(),
             state: ListState::default(),
             selected_items: Vec::new(),
-            is_file: false,
-            is_dir: false,
             is_active: true,
             create_folder: FolderCreation::default(),
             creat_file: FileCreation::default(),
+            show_hidden,
+            sort,
+            revealed: None,
+            cursors: HashMap::new(),
+            back: Vec::new(),
+            forward: Vec::new(),
         }
     }
-}
 
-impl Default for SelectedWidget {
-    fn default() -> Self {
-        Self {
-            file_info: FileInfo::default(),
-            file_preview: FilePreview::default(),
-            file_list: FileList::default(),
-            file_selection: FileSelection::default(),
+    /// Opens `path` for browsing, failing if it is not a readable directory.
+    fn open(path: &Path, show_hidden: bool, sort: SortMode) -> Result<FileList, String> {
+        let path = fs::canonicalize(path).map_err(|err| format!("{}: {}", path.display(), err))?;
+        if !path.is_dir() {
+            return Err(format!("{}: Not a directory", path.display()));
         }
+        fs::read_dir(&path).map_err(|err| format!("{}: {}", path.display(), err))?;
+
+        let mut notes = FileList::new(path, show_hidden, sort);
+        notes.update();
+        Ok(notes)
     }
-}
 
-impl Default for FilePreview {
-    fn default() -> Self {
-        Self {
-            is_active: false,
-            scroll: Scroll::default(),
+    /// Moves the cursor onto the entry called `name`.
+    fn select_name(&mut self, name: &str) -> bool {
+        let index = self
+            .items
+            .iter()
+            .position(|item| item.split('/').next_back() == Some(name));
+        if index.is_some() {
+            self.state.select(index);
         }
+        index.is_some()
     }
-}
 
-impl Default for FileInfo {
-    fn default() -> Self {
-        Self {
-            is_active: false,
-            scroll: Scroll::default(),
+    /// Like `select_name`, but also finds hidden entries while hidden files
+    /// are off: the entry stays listed until the listing moves to another
+    /// directory, the setting stays as it is.
+    fn reveal_name(&mut self, name: &str) -> bool {
+        if self.select_name(name) {
+            return true;
         }
+        self.revealed = Some(name.to_string());
+        self.update();
+        self.select_name(name)
     }
-}
 
-impl Default for FileSelection {
-    fn default() -> Self {
-        Self {
-            is_active: false,
-            scroll: Scroll::default(),
+    fn update(&mut self) {
+        self.items = self.entries(&self.path);
+
+        if let Some(name) = &self.revealed {
+            let path = self.path.join(name).to_string_lossy().to_string();
+            if !self.items.contains(&path) && fs::symlink_metadata(&path).is_ok() {
+                self.items.push(path);
+                self.sort.sort(&mut self.items);
+            }
         }
     }
-}
 
-impl Default for Scroll {
-    fn default() -> Self {
-        Self { y: 0, x: 0 }
+    /// Adds every entry of this listing to the selection.
+    fn select_all(&mut self) {
+        for item in &self.items {
+            if !self.selected_items.contains(item) {
+                self.selected_items.push(item.clone());
+            }
+        }
     }
-}
 
-impl Default for FolderCreation {
-    fn default() -> Self {
-        Self {
-            is_active: false,
-            user_input: String::new(),
+    /// Selects the entries of this listing that aren't selected and
+    /// deselects the others. Entries of other directories stay selected.
+    fn invert_selection(&mut self) {
+        let (here, elsewhere): (Vec<String>, Vec<String>) =
+            std::mem::take(&mut self.selected_items)
+                .into_iter()
+                .partition(|item| self.items.contains(item));
+        self.selected_items = elsewhere;
+        for item in &self.items {
+            if !here.contains(item) {
+                self.selected_items.push(item.clone());
+            }
         }
     }
-}
 
-impl Default for FileCreation {
-    fn default() -> Self {
-        Self {
-            is_active: false,
-            user_input: String::new(),
+    /// Entries of this listing whose name matches `pattern`.
+    fn matching(&self, pattern: &NamePattern) -> Vec<&String> {
+        self.items
+            .iter()
+            .filter(|item| pattern.matches(item.split('/').next_back().unwrap_or(item)))
+            .collect()
+    }
+
+    /// Selects, or with `select` false deselects, the entries matching
+    /// `pattern`. Returns how many entries changed.
+    fn select_matching(&mut self, pattern: &NamePattern, select: bool) -> usize {
+        let matching: Vec<String> = self.matching(pattern).into_iter().cloned().collect();
+        let before = self.selected_items.len();
+        if select {
+            for item in matching {
+                if !self.selected_items.contains(&item) {
+                    self.selected_items.push(item);
+                }
+            }
+            self.selected_items.len() - before
+        } else {
+            self.selected_items.retain(|item| !matching.contains(item));
+            before - self.selected_items.len()
         }
     }
-}
 
-impl FileList {
-    fn update(self: &mut Self) {
-        self.items.clear();
+    /// Bytes taken by the selected entries. Directories count their own
+    /// size only, walking them on every frame would be too slow.
+    fn selection_size(&self) -> u64 {
+        self.selected_items
+            .iter()
+            .filter_map(|item| fs::symlink_metadata(item).ok())
+            .map(|metadata| metadata.len())
+            .sum()
+    }
+
+    /// Entries of `path` as this listing shows them, with its hidden and sort settings.
+    fn entries(&self, path: &Path) -> Vec<String> {
+        preview::list_dir(path, self.show_hidden, self.sort)
+    }
+
+    /// Reads the directory again, keeping the cursor on the same entry if it still exists.
+    fn reload(&mut self) {
+        let name = self.selected_item();
+        let index = self.state.selected();
+
+        self.update();
+
+        if !self.select_name(&name) {
+            let last = self.items.len().saturating_sub(1);
+            self.state.select(index.map(|index| index.min(last)));
+        }
+    }
+
+    fn dir_next(&mut self) {
+        if let Some(index) = self.state.selected()
+            && let Some(entry) = self.items.get(index)
+            && Path::new(entry).is_dir()
+        {
+            self.go_to(&PathBuf::from(entry));
+        }
+    }
 
-        let entries = match fs::read_dir(&self.path) {
-            Ok(entries) => entries,
-            Err(_) => return,
+    fn dir_back(&mut self) {
+        let child = self.dir().to_path_buf();
+        let Some(parent) = child.parent() else {
+            return;
         };
+        self.go_to(parent);
+
+        // land on the directory we came from
+        let name = child
+            .file_name()
+            .map(|name| name.to_string_lossy().to_string())
+            .unwrap_or_default();
+        if !self.select_name(&name) {
+            self.restore_cursor();
+        }
+    }
 
-        let items: Vec<String> = entries
-            .filter_map(Result::ok)
-            .map(|entry| entry.path().to_string_lossy().to_string())
-            .collect();
+    /// Browses `dir`, recording the way back in the history.
+    fn go_to(&mut self, dir: &Path) {
+        if dir == self.dir() {
+            return;
+        }
 
-        //items.sort_by(|a, b| compare(a, b));
-        self.items = items;
+        self.back.push(self.dir().to_path_buf());
+        self.forward.clear();
+        self.enter(dir);
     }
 
-    fn dir_next(self: &mut Self) {
-        if let Some(index) = self.state.selected() {
-            if let Some(entry) = self.items.get(index) {
-                self.path = PathBuf::from(entry.as_str());
-            }
+    fn history_back(&mut self) {
+        if let Some(dir) = self.back.pop() {
+            self.forward.push(self.dir().to_path_buf());
+            self.enter(&dir);
         }
+    }
 
-        if self.path.is_dir() {
-            FileList::update(self);
-            self.state.select(Some(0));
+    fn history_forward(&mut self) {
+        if let Some(dir) = self.forward.pop() {
+            self.back.push(self.dir().to_path_buf());
+            self.enter(&dir);
         }
     }
 
-    fn dir_back(self: &mut Self) {
-        self.path.pop();
+    fn enter(&mut self, dir: &Path) {
+        self.remember_cursor();
+        self.path = dir.to_path_buf();
+        self.revealed = None;
         self.update();
-        self.state.select(Some(0));
+        self.restore_cursor();
     }
 
-    fn selected_item(self: &mut Self) -> String {
-        let mut entry_name = String::new();
-
-        if let Some(selected) = self.state.selected() {
-            if let Some(item) = self.items.get(selected) {
-                entry_name = item.split('/').last().unwrap_or("None").to_string();
-                // use entry_name here
-            }
+    fn remember_cursor(&mut self) {
+        let name = self.selected_item();
+        if !name.is_empty() {
+            let dir = self.dir().to_path_buf();
+            self.cursors.insert(dir, name);
         }
-        entry_name
     }
 
-    fn dir_ls(self: &mut Self) -> String {
-        let path: String;
-        let none: String = String::from("none");
+    /// Puts the cursor where it was when the directory was left, or on the first entry.
+    fn restore_cursor(&mut self) {
+        let name = self.cursors.get(self.dir()).cloned();
+        if !name.is_some_and(|name| self.select_name(&name)) {
+            self.state.select(Some(0));
+        }
+    }
 
-        if let Some(index) = self.state.selected() {
-            if let Some(item) = self.items.get(index) {
-                path = item.to_string();
-                let output = Command::new("bash")
-                    .arg("-c") // Use bash with -c to execute a command
-                    .arg(format!("ls {}", path))
-                    .output()
-                    .expect("failed to execute process");
+    /// Puts the cursor back on the first entry if it points past the end.
+    fn clamp_cursor(&mut self) {
+        match self.state.selected() {
+            Some(index) if index < self.items.len() => {}
+            _ => self.state.select(Some(0)),
+        }
+    }
 
-                return String::from_utf8_lossy(&output.stdout).to_string();
-            } else {
-                return none;
-            }
+    /// The directory being browsed, `path` may point at a file after `dir_next`.
+    fn dir(&self) -> &Path {
+        if self.path.is_dir() {
+            &self.path
         } else {
-            return none;
+            self.path.parent().unwrap_or(Path::new("/"))
+        }
+    }
+
+    fn selected_item(&mut self) -> String {
+        let mut entry_name = String::new();
+
+        if let Some(selected) = self.state.selected()
+            && let Some(item) = self.items.get(selected)
+        {
+            entry_name = item.split('/').next_back().unwrap_or("None").to_string();
+            // use entry_name here
         }
+        entry_name
     }
 
-    fn create_folder(self: &mut Self) {
+    fn create_folder(&mut self) {
         let mut path: String;
 
-        if let Some(index) = self.state.selected() {
-            if let Some(item) = self.items.get(index) {
-                path = item.to_string();
+        if let Some(index) = self.state.selected()
+            && let Some(item) = self.items.get(index)
+        {
+            path = item.to_string();
 
-                path = if let Some(index) = path.rfind('/') {
-                    path[..index].to_string()
-                } else {
-                    path
-                };
+            path = if let Some(index) = path.rfind('/') {
+                path[..index].to_string()
+            } else {
+                path
+            };
 
-                Command::new("mkdir")
-                    .arg(format!("{}/{}", path, self.create_folder.user_input))
-                    .output()
-                    .expect("failed to execute process");
-                self.update();
-                self.create_folder.user_input.clear();
-            }
+            Command::new("mkdir")
+                .arg(format!("{}/{}", path, self.create_folder.user_input))
+                .output()
+                .expect("failed to execute process");
+            self.update();
+            self.create_folder.user_input.clear();
         }
     }
-    fn create_file(self: &mut Self) {
+    fn create_file(&mut self) {
         let mut path: String;
 
-        if let Some(index) = self.state.selected() {
-            if let Some(item) = self.items.get(index) {
-                path = item.to_string();
+        if let Some(index) = self.state.selected()
+            && let Some(item) = self.items.get(index)
+        {
+            path = item.to_string();
 
-                path = if let Some(index) = path.rfind('/') {
-                    path[..index].to_string()
-                } else {
-                    path
-                };
+            path = if let Some(index) = path.rfind('/') {
+                path[..index].to_string()
+            } else {
+                path
+            };
 
-                Command::new("touch")
-                    .arg(format!(
-                        "{}/{}",
-                        path,
-                        self.creat_file.user_input.to_string()
-                    ))
-                    .output()
-                    .expect("failed to execute process");
-                self.update();
-                self.creat_file.user_input.clear();
-            }
+            Command::new("touch")
+                .arg(format!("{}/{}", path, self.creat_file.user_input))
+                .output()
+                .expect("failed to execute process");
+            self.update();
+            self.creat_file.user_input.clear();
         }
     }
 }
 
 impl SelectedWidget {
-    fn change_widget(self: &mut Self) {
+    fn change_widget(&mut self) {
         if self.file_list.is_active {
             self.file_list.is_active = false;
             self.file_preview.is_active = true;
@@ -318,13 +717,131 @@ impl SelectedWidget {
             self.file_list.is_active = true;
         }
     }
+
+    fn focus(&mut self, pane: Pane) {
+        self.file_list.is_active = pane == Pane::List;
+        self.file_preview.is_active = pane == Pane::Preview;
+        self.file_info.is_active = pane == Pane::Info;
+        self.file_selection.is_active = pane == Pane::Selection;
+    }
+
+    fn scroll_mut(&mut self, pane: Pane) -> Option<&mut Scroll> {
+        match pane {
+            Pane::List => None,
+            Pane::Preview => Some(&mut self.file_preview.scroll),
+            Pane::Info => Some(&mut self.file_info.scroll),
+            Pane::Selection => None,
+        }
+    }
 }
 
 impl App {
-    pub fn run(&mut self, terminal: &mut DefaultTerminal) -> io::Result<()> {
+    pub fn from_args(args: &cli::Args) -> Result<App, String> {
+        let config = Config::load(args.config.as_deref())?;
+        let show_hidden = args.show_hidden || config.show_hidden;
+        let sort = args.sort.unwrap_or(config.sort);
+
+        let start = match &args.path {
+            Some(path) => path.clone(),
+            None => std::env::current_dir().map_err(|err| format!("current directory: {}", err))?,
+        };
+
+        let mut notes = match &args.select {
+            Some(select) => {
+                let select = start.join(select);
+                let name = select
+                    .file_name()
+                    .map(|name| name.to_string_lossy().to_string())
+                    .ok_or_else(|| format!("{}: nothing to select", select.display()))?;
+                if fs::symlink_metadata(&select).is_err() {
+                    return Err(format!("{}: No such file or directory", select.display()));
+                }
+
+                let parent = select.parent().unwrap_or(Path::new("/"));
+                let mut notes = FileList::open(parent, show_hidden, sort)?;
+                notes.reveal_name(&name);
+                notes
+            }
+            None => FileList::open(&start, show_hidden, sort)?,
+        };
+
+        let picker = args.pick.then(|| Picker {
+            dirs_only: args.pick_dirs,
+            extensions: args.pick_ext.clone(),
+            output: args.choosefiles.clone(),
+            null_separated: args.print0,
+            picked: None,
+        });
+
+        let openers = if config.openers.is_empty() {
+            opener::default_openers()
+        } else {
+            config.openers
+        };
+
+        let recent = Recent::default_path()
+            .map(|path| Recent::load(&path))
+            .unwrap_or_default();
+        let bookmarks = Bookmarks::default_path()
+            .map(|path| Bookmarks::load(&path))
+            .unwrap_or_default();
+        let frecency = Frecency::default_path()
+            .map(|path| Frecency::load(&path))
+            .unwrap_or_default();
+        if config.keep_selection
+            && let Some(path) = selection::default_path()
+        {
+            notes.selected_items = selection::load(&path);
+        }
+
+        Ok(App {
+            notes,
+            recent,
+            bookmarks,
+            frecency,
+            icons: config.icons,
+            copy_method: config.clipboard,
+            keep_selection: config.keep_selection,
+            picker,
+            openers,
+            ..App::default()
+        })
+    }
+
+    pub fn run(&mut self, terminal: &mut tui::Tui) -> io::Result<()> {
         while !self.exit {
-            terminal.draw(|frame| self.draw(frame))?;
-            self.handle_events().expect("PANICCCCC");
+            if self.dirty {
+                terminal.draw(|frame| self.draw(frame))?;
+                self.dirty = false;
+            }
+
+            let event = self.events.next()?;
+            self.handle_event(event);
+
+            if let Some((command, process)) = self.launcher.foreground.take() {
+                let status = launcher::run_foreground(terminal, process);
+                self.log_status(&command, &status);
+                self.notes.reload();
+                self.preview.invalidate();
+                self.dirty = true;
+            }
+
+            let dir = self.last_dir().to_path_buf();
+            if self.visited.as_ref() != Some(&dir) {
+                self.recent.visit(&dir);
+                self.frecency.visit(&dir);
+                self.visited = Some(dir.clone());
+            }
+            let selected = self.selected_path();
+            self.watcher.watch(&dir, selected.as_deref().map(Path::new));
+            if let Some(path) = selected {
+                self.preview.request(
+                    &path,
+                    self.notes.show_hidden,
+                    self.notes.sort,
+                    &self.events.sender(),
+                );
+            }
         }
         Ok(())
     }
@@ -333,62 +850,299 @@ impl App {
         frame.render_widget(self, frame.area());
     }
 
-    fn handle_events(&mut self) -> io::Result<()> {
-        match event::read()? {
-            Event::Key(key_event) if key_event.kind == KeyEventKind::Press => {
-                self.handle_key_events(key_event)
+    fn handle_event(&mut self, event: AppEvent) {
+        match event {
+            AppEvent::Input(Event::Key(key_event)) => {
+                if key_event.kind == KeyEventKind::Press {
+                    self.handle_key_events(key_event);
+                    self.update_visual();
+                }
+                self.dirty = true;
             }
-            _ => {}
-        }
-        Ok(())
-    }
-
-    fn handle_key_events(&mut self, key_event: KeyEvent) {
-        if self.notes.create_folder.is_active {
-            match key_event {
-                KeyEvent {
-                    code: KeyCode::Char('f'),
-                    modifiers,
-                    ..
-                } => {
-                    if modifiers.contains(KeyModifiers::CONTROL) {
-                        self.notes.create_folder.is_active = !self.notes.create_folder.is_active;
-                    } else {
-                        self.notes.create_folder.user_input.push('f');
-                    }
+            AppEvent::Input(Event::Mouse(mouse_event)) => {
+                if self.handle_mouse_event(mouse_event) {
+                    self.update_visual();
+                    self.dirty = true;
                 }
-                KeyEvent {
-                    code: KeyCode::Backspace,
-                    ..
-                } => {
-                    self.notes.create_folder.user_input.pop();
+            }
+            AppEvent::Input(_) => self.dirty = true,
+            AppEvent::Tick => {
+                if self.watcher.settled() {
+                    self.notes.reload();
+                    self.preview.invalidate();
+                    self.dirty = true;
                 }
-                KeyEvent {
-                    code: KeyCode::Char(c),
-                    ..
-                } => {
-                    self.notes.create_folder.user_input.push(c);
+                if !self.launcher.jobs.is_empty() {
+                    self.spinner += 1;
+                    self.dirty = true;
                 }
-
-                KeyEvent {
-                    code: KeyCode::Enter,
-                    ..
-                } => {
-                    self.notes.create_folder();
+                if self.log.expire_flash() {
+                    self.dirty = true;
                 }
-                _ => {}
             }
-        } else if self.notes.creat_file.is_active {
-            match key_event {
-                KeyEvent {
-                    code: KeyCode::Char('t'),
-                    modifiers,
-                    ..
-                } => {
-                    if modifiers.contains(KeyModifiers::CONTROL) {
-                        self.notes.creat_file.is_active = !self.notes.creat_file.is_active;
+            AppEvent::FsChange => self.watcher.record(),
+            AppEvent::JobFinished { id, status } => {
+                if let Some(job) = self.launcher.jobs.remove(&id) {
+                    if job.show_output {
+                        let output = std::mem::take(&mut *job.output.lock().unwrap());
+                        let mut text = output.text;
+                        if output.truncated {
+                            text.insert_str(0, "[earlier output dropped]\n");
+                        }
+                        self.show_command_output(job.command, text, status);
                     } else {
-                        self.notes.creat_file.user_input.push('t');
+                        self.log_status(&job.command, &status);
+                    }
+                    self.notes.reload();
+                    self.dirty = true;
+                }
+            }
+            AppEvent::ProcessExited { command, status } => {
+                self.log_status(&command, &status);
+                self.dirty = true;
+            }
+            AppEvent::PreviewReady { path, content } => {
+                if self.preview.receive(path, content) {
+                    self.dirty = true;
+                }
+            }
+        }
+    }
+
+    /// Returns whether anything changed.
+    fn handle_mouse_event(&mut self, mouse_event: MouseEvent) -> bool {
+        const DOUBLE_CLICK: Duration = Duration::from_millis(400);
+
+        // only the main view reacts to the mouse, not the prompts and overlays
+        if self.help
+            || self.notes.create_folder.is_active
+            || self.notes.creat_file.is_active
+            || self.command_prompt.is_active
+            || self.command_output.is_some()
+            || self.open_with.is_some()
+            || self.recent_menu.is_some()
+            || self.bookmark_menu.is_some()
+            || self.jump_prompt.is_some()
+            || self.goto_prompt.is_some()
+            || self.pattern_prompt.is_some()
+            || self.log_panel.is_some()
+        {
+            return false;
+        }
+
+        if mouse_event.kind == MouseEventKind::Down(MouseButton::Left)
+            && let Some(tab) = self.tab_at(mouse_event.column, mouse_event.row)
+        {
+            self.switch_tab(tab);
+            return true;
+        }
+
+        if mouse_event.kind == MouseEventKind::Down(MouseButton::Left)
+            && self
+                .areas
+                .other_pane
+                .contains(layout::Position::new(mouse_event.column, mouse_event.row))
+        {
+            self.switch_pane();
+            return true;
+        }
+
+        let Some(pane) = self.pane_at(mouse_event.column, mouse_event.row) else {
+            return false;
+        };
+
+        match mouse_event.kind {
+            MouseEventKind::Down(MouseButton::Left) => {
+                self.selected_widget.focus(pane);
+                if pane != Pane::List {
+                    return true;
+                }
+
+                let Some(index) = self.list_index_at(mouse_event.row) else {
+                    return true;
+                };
+                if self.notes.state.selected() != Some(index) {
+                    self.selected_widget.file_preview.scroll = Scroll::default();
+                }
+                self.notes.state.select(Some(index));
+
+                if mouse_event.modifiers.contains(KeyModifiers::CONTROL) {
+                    self.select_files();
+                    self.last_click = None;
+                    return true;
+                }
+
+                let now = Instant::now();
+                let double_click = matches!(
+                    self.last_click,
+                    Some((last, at)) if last == index && now - at <= DOUBLE_CLICK
+                );
+                self.last_click = Some((index, now));
+
+                if double_click {
+                    self.last_click = None;
+                    self.activate();
+                }
+                true
+            }
+            MouseEventKind::ScrollUp | MouseEventKind::ScrollDown => {
+                let up = mouse_event.kind == MouseEventKind::ScrollUp;
+                if pane == Pane::Selection {
+                    self.move_selection_cursor(up);
+                    return true;
+                }
+                match self.selected_widget.scroll_mut(pane) {
+                    Some(scroll) if up => scroll.y = scroll.y.saturating_sub(1),
+                    Some(scroll) => scroll.y += 1,
+                    None => {
+                        // scroll the list without wrapping around at the ends
+                        let len = self.notes.items.len();
+                        let i = self.notes.state.selected().unwrap_or(0);
+                        let i = if up {
+                            i.saturating_sub(1)
+                        } else {
+                            (i + 1).min(len.saturating_sub(1))
+                        };
+                        self.selected_widget.file_preview.scroll = Scroll::default();
+                        self.notes.state.select(Some(i));
+                    }
+                }
+                true
+            }
+            _ => false,
+        }
+    }
+
+    fn pane_at(&self, column: u16, row: u16) -> Option<Pane> {
+        let position = layout::Position::new(column, row);
+        [
+            (self.areas.list, Pane::List),
+            (self.areas.preview, Pane::Preview),
+            (self.areas.info, Pane::Info),
+            (self.areas.selection, Pane::Selection),
+        ]
+        .into_iter()
+        .find(|(area, _)| area.contains(position))
+        .map(|(_, pane)| pane)
+    }
+
+    /// Labels of all tabs for the tab bar, in order.
+    fn tab_names(&self) -> Vec<String> {
+        self.tabs[..self.tab]
+            .iter()
+            .chain([&self.notes])
+            .chain(&self.tabs[self.tab..])
+            .enumerate()
+            .map(|(i, list)| {
+                let name = list
+                    .dir()
+                    .file_name()
+                    .map(|name| name.to_string_lossy().to_string())
+                    .unwrap_or_else(|| "/".to_string());
+                format!("{} {}", i + 1, name)
+            })
+            .collect()
+    }
+
+    /// The tab whose label is drawn at `column`, `row`.
+    fn tab_at(&self, column: u16, row: u16) -> Option<usize> {
+        if !self.areas.tabs.contains(layout::Position::new(column, row)) {
+            return None;
+        }
+
+        // every label is padded by a space on both sides and followed by a divider
+        let mut x = self.areas.tabs.x;
+        for (i, name) in self.tab_names().iter().enumerate() {
+            x += name.chars().count() as u16 + 2;
+            if column < x {
+                return Some(i);
+            }
+            x += 1;
+        }
+        None
+    }
+
+    /// The list entry drawn on screen row `row`, if any.
+    fn list_index_at(&self, row: u16) -> Option<usize> {
+        let area = self.areas.list;
+        // skip the border
+        if row <= area.y || row + 1 >= area.y + area.height {
+            return None;
+        }
+
+        let index = self.areas.list_offset + (row - area.y - 1) as usize;
+        (index < self.notes.items.len()).then_some(index)
+    }
+
+    /// What Enter does: pick, enter the directory or open the file.
+    fn activate(&mut self) {
+        let is_dir = self
+            .selected_path()
+            .is_some_and(|path| Path::new(&path).is_dir());
+
+        if self.picker.is_some() {
+            self.pick();
+        } else if is_dir {
+            self.notes.dir_next();
+        } else {
+            self.open_via_app();
+        }
+    }
+
+    fn selected_path(&self) -> Option<String> {
+        self.notes
+            .state
+            .selected()
+            .and_then(|index| self.notes.items.get(index))
+            .cloned()
+    }
+
+    fn handle_key_events(&mut self, key_event: KeyEvent) {
+        if self.notes.create_folder.is_active {
+            match key_event {
+                KeyEvent {
+                    code: KeyCode::Char('f'),
+                    modifiers,
+                    ..
+                } => {
+                    if modifiers.contains(KeyModifiers::CONTROL) {
+                        self.notes.create_folder.is_active = !self.notes.create_folder.is_active;
+                    } else {
+                        self.notes.create_folder.user_input.push('f');
+                    }
+                }
+                KeyEvent {
+                    code: KeyCode::Backspace,
+                    ..
+                } => {
+                    self.notes.create_folder.user_input.pop();
+                }
+                KeyEvent {
+                    code: KeyCode::Char(c),
+                    ..
+                } => {
+                    self.notes.create_folder.user_input.push(c);
+                }
+
+                KeyEvent {
+                    code: KeyCode::Enter,
+                    ..
+                } => {
+                    self.notes.create_folder();
+                }
+                _ => {}
+            }
+        } else if self.notes.creat_file.is_active {
+            match key_event {
+                KeyEvent {
+                    code: KeyCode::Char('t'),
+                    modifiers,
+                    ..
+                } => {
+                    if modifiers.contains(KeyModifiers::CONTROL) {
+                        self.notes.creat_file.is_active = !self.notes.creat_file.is_active;
+                    } else {
+                        self.notes.creat_file.user_input.push('t');
                     }
                 }
                 KeyEvent {
@@ -412,12 +1166,168 @@ impl App {
                 }
                 _ => {}
             }
+        } else if self.command_prompt.is_active {
+            match key_event.code {
+                KeyCode::Backspace => {
+                    self.command_prompt.user_input.pop();
+                }
+                KeyCode::Char(c) => self.command_prompt.user_input.push(c),
+                KeyCode::Enter => self.run_command(),
+                KeyCode::Esc => {
+                    self.command_prompt.is_active = false;
+                    self.command_prompt.user_input.clear();
+                }
+                _ => {}
+            }
+        } else if let Some(output) = &mut self.command_output {
+            match key_event.code {
+                KeyCode::Up => output.scroll.y = output.scroll.y.saturating_sub(1),
+                KeyCode::Down => output.scroll.y += 1,
+                KeyCode::Left => output.scroll.x = output.scroll.x.saturating_sub(1),
+                KeyCode::Right => output.scroll.x += 1,
+                KeyCode::Esc | KeyCode::Enter | KeyCode::Char('q') => self.command_output = None,
+                _ => {}
+            }
+        } else if let Some(prefix) = self.pending_key.take() {
+            if let KeyCode::Char(key) = key_event.code {
+                match prefix {
+                    'm' => self.set_bookmark(key),
+                    'Y' => self.copy_to_system(key),
+                    _ => self.jump_to_mark(key),
+                }
+            }
+        } else if self.bookmark_menu.is_some() {
+            self.handle_bookmark_menu_key(key_event);
+        } else if let Some(scroll) = &mut self.log_panel {
+            match key_event.code {
+                KeyCode::Up => scroll.y += 1,
+                KeyCode::Down => scroll.y = scroll.y.saturating_sub(1),
+                KeyCode::PageUp => scroll.y += 10,
+                KeyCode::PageDown => scroll.y = scroll.y.saturating_sub(10),
+                KeyCode::Esc | KeyCode::Char('l') | KeyCode::Char('q') => self.log_panel = None,
+                _ => {}
+            }
+        } else if let Some(prompt) = &mut self.pattern_prompt {
+            match key_event.code {
+                KeyCode::Backspace => {
+                    prompt.user_input.pop();
+                }
+                KeyCode::Char(c) => prompt.user_input.push(c),
+                KeyCode::Enter => {
+                    if let Some(prompt) = self.pattern_prompt.take() {
+                        self.select_pattern(&prompt.user_input, prompt.select);
+                    }
+                }
+                KeyCode::Esc => self.pattern_prompt = None,
+                _ => {}
+            }
+        } else if let Some(prompt) = &mut self.goto_prompt {
+            match key_event.code {
+                KeyCode::Backspace => {
+                    prompt.user_input.pop();
+                    self.update_goto_candidates();
+                }
+                KeyCode::Char(c) => {
+                    prompt.user_input.push(c);
+                    self.update_goto_candidates();
+                }
+                KeyCode::Tab => self.complete_goto(),
+                KeyCode::Enter => {
+                    let input = std::mem::take(&mut prompt.user_input);
+                    self.goto_prompt = None;
+                    self.go_to_input(&input);
+                }
+                KeyCode::Esc => self.goto_prompt = None,
+                _ => {}
+            }
+        } else if let Some(prompt) = &mut self.jump_prompt {
+            match key_event.code {
+                KeyCode::Backspace => {
+                    prompt.user_input.pop();
+                    self.update_jump_matches();
+                }
+                KeyCode::Char(c) => {
+                    prompt.user_input.push(c);
+                    self.update_jump_matches();
+                }
+                KeyCode::Up => cursor_up(&mut prompt.state, prompt.matches.len()),
+                KeyCode::Down => cursor_down(&mut prompt.state, prompt.matches.len()),
+                // complete the query to the chosen directory
+                KeyCode::Tab => {
+                    if let Some(dir) = prompt.state.selected().and_then(|i| prompt.matches.get(i)) {
+                        prompt.user_input = dir.to_string_lossy().to_string();
+                        self.update_jump_matches();
+                    }
+                }
+                KeyCode::Enter => {
+                    let chosen = prompt
+                        .state
+                        .selected()
+                        .and_then(|i| prompt.matches.get(i))
+                        .cloned();
+                    let query = std::mem::take(&mut prompt.user_input);
+                    self.jump_prompt = None;
+                    match chosen {
+                        Some(dir) => self.notes.go_to(&dir),
+                        None => self
+                            .log
+                            .warn("jump", format!("No directory matches `{}`", query)),
+                    }
+                }
+                KeyCode::Esc => self.jump_prompt = None,
+                _ => {}
+            }
+        } else if let Some(menu) = &mut self.recent_menu {
+            let len = self.recent.dirs.len();
+            match key_event.code {
+                KeyCode::Up => cursor_up(menu, len),
+                KeyCode::Down => cursor_down(menu, len),
+                KeyCode::Enter => {
+                    let chosen = menu
+                        .selected()
+                        .and_then(|i| self.recent.dirs.get(i))
+                        .cloned();
+                    self.recent_menu = None;
+                    match chosen {
+                        Some(dir) if dir.is_dir() => self.notes.go_to(&dir),
+                        Some(dir) => self
+                            .log
+                            .error("recent", format!("{}: Not a directory", dir.display())),
+                        None => {}
+                    }
+                }
+                KeyCode::Esc | KeyCode::Char('R') | KeyCode::Char('q') => self.recent_menu = None,
+                _ => {}
+            }
+        } else if let Some(menu) = &mut self.open_with {
+            match key_event.code {
+                KeyCode::Up => cursor_up(&mut menu.state, menu.openers.len()),
+                KeyCode::Down => cursor_down(&mut menu.state, menu.openers.len()),
+                KeyCode::Enter => {
+                    let chosen = menu
+                        .state
+                        .selected()
+                        .and_then(|i| menu.openers.get(i))
+                        .copied();
+                    let path = menu.path.clone();
+                    self.open_with = None;
+                    if let Some(index) = chosen {
+                        self.run_opener(index, &path);
+                    }
+                }
+                KeyCode::Esc | KeyCode::Char('o') | KeyCode::Char('q') => self.open_with = None,
+                _ => {}
+            }
         } else {
             match key_event {
                 KeyEvent {
                     code: KeyCode::Char('q'),
                     ..
                 } => self.exit(),
+                KeyEvent {
+                    code: KeyCode::Char('Q'),
+                    ..
+                } => self.exit_without_cd(),
                 KeyEvent {
                     code: KeyCode::Up, ..
                 } => self.previous(),
@@ -425,19 +1335,6 @@ impl App {
                     code: KeyCode::Down,
                     ..
                 } => self.next(),
-                KeyEvent {
-                    code: KeyCode::End, ..
-                } => self.input.push_str(self.notes.path.to_str().unwrap()),
-                KeyEvent {
-                    code: KeyCode::Insert,
-                    ..
-                } => self.input.push_str(
-                    self.notes
-                        .items
-                        .get(self.notes.state.selected().unwrap())
-                        .unwrap()
-                        .as_str(),
-                ),
                 KeyEvent {
                     code: KeyCode::PageUp,
                     ..
@@ -446,25 +1343,212 @@ impl App {
                     code: KeyCode::PageDown,
                     ..
                 } => self.notes.dir_back(),
+                KeyEvent {
+                    code: KeyCode::Left,
+                    ..
+                } => self.notes.dir_back(),
+                KeyEvent {
+                    code: KeyCode::Right,
+                    ..
+                } if self
+                    .selected_path()
+                    .is_some_and(|path| Path::new(&path).is_dir()) =>
+                {
+                    self.notes.dir_next()
+                }
+                KeyEvent {
+                    code: KeyCode::Char('H'),
+                    ..
+                } => self.notes.history_back(),
+                KeyEvent {
+                    code: KeyCode::Char('L'),
+                    ..
+                } => self.notes.history_forward(),
+                KeyEvent {
+                    code: KeyCode::Char('R'),
+                    ..
+                } => {
+                    // the current directory is always on top, start at the one before
+                    let start = self.recent.dirs.len().min(2).saturating_sub(1);
+                    self.recent_menu = Some(ListState::default().with_selected(Some(start)));
+                }
+                KeyEvent {
+                    code: KeyCode::Char('M'),
+                    ..
+                } => {
+                    self.miller = !self.miller;
+                    self.other_pane = None;
+                    self.selected_widget.focus(Pane::List);
+                }
                 KeyEvent {
                     code: KeyCode::Enter,
                     ..
-                } => self.open_via_app(),
+                } => {
+                    if self.picker.is_some() {
+                        self.pick();
+                    } else {
+                        self.open_via_app();
+                    }
+                }
+                KeyEvent {
+                    code: KeyCode::Esc, ..
+                } if self.visual.is_some() => {
+                    if let Some(visual) = self.visual.take() {
+                        self.notes.selected_items = visual.before;
+                    }
+                }
+                KeyEvent {
+                    code: KeyCode::Esc, ..
+                } if self.picker.is_some() => self.exit(),
+                KeyEvent {
+                    code: KeyCode::Char('o'),
+                    ..
+                } => self.show_open_with(),
+                KeyEvent {
+                    code: KeyCode::Char(':' | '!'),
+                    ..
+                } => self.command_prompt.is_active = true,
+                KeyEvent {
+                    code: KeyCode::Char('S'),
+                    ..
+                } => {
+                    let process = launcher::subshell(self.last_dir(), &self.notes.selected_items);
+                    self.launcher.foreground = Some((String::from("shell"), process));
+                }
+                KeyEvent {
+                    code: KeyCode::Char(' ') | KeyCode::Delete,
+                    ..
+                } if self.selected_widget.file_selection.is_active => self.unselect_at_cursor(),
                 KeyEvent {
                     code: KeyCode::Char(' '),
                     ..
                 } => self.select_files(),
+                KeyEvent {
+                    code: KeyCode::Char('v'),
+                    ..
+                } => self.toggle_visual(),
+                KeyEvent {
+                    code: KeyCode::Char('a'),
+                    ..
+                } => self.notes.select_all(),
+                KeyEvent {
+                    code: KeyCode::Char('i'),
+                    ..
+                } => self.notes.invert_selection(),
+                KeyEvent {
+                    code: KeyCode::Char(sign @ ('+' | '-')),
+                    ..
+                } => {
+                    self.pattern_prompt = Some(PatternPrompt {
+                        user_input: String::new(),
+                        select: sign == '+',
+                    })
+                }
                 KeyEvent {
                     code: KeyCode::Tab, ..
-                } => self.selected_widget.change_widget(),
+                } => {
+                    if self.other_pane.is_some() {
+                        self.switch_pane();
+                    } else {
+                        self.selected_widget.change_widget();
+                    }
+                }
+                KeyEvent {
+                    code: KeyCode::Char('|'),
+                    ..
+                } => self.toggle_dual_pane(),
+                KeyEvent {
+                    code: KeyCode::Char('s'),
+                    ..
+                } => self.swap_panes(),
+                KeyEvent {
+                    code: KeyCode::Char('='),
+                    ..
+                } => self.sync_panes(),
+                KeyEvent {
+                    code: KeyCode::Char('x'),
+                    modifiers,
+                    ..
+                } if modifiers.contains(KeyModifiers::CONTROL) => self.move_files(),
+                KeyEvent {
+                    code: KeyCode::Char(prefix @ ('m' | '\'' | 'Y')),
+                    ..
+                } => self.pending_key = Some(prefix),
+                KeyEvent {
+                    code: KeyCode::Char('l'),
+                    ..
+                } => self.log_panel = Some(Scroll::default()),
+                KeyEvent {
+                    code: KeyCode::Char('g'),
+                    ..
+                } => {
+                    self.goto_prompt = Some(GotoPrompt::default());
+                    self.update_goto_candidates();
+                }
+                KeyEvent {
+                    code: KeyCode::Char('z'),
+                    ..
+                } => {
+                    self.jump_prompt = Some(JumpPrompt::default());
+                    self.update_jump_matches();
+                }
+                KeyEvent {
+                    code: KeyCode::Char('b'),
+                    ..
+                } => {
+                    self.bookmark_menu = Some(BookmarkMenu {
+                        state: ListState::default().with_selected(Some(0)),
+                        rename: None,
+                    })
+                }
                 KeyEvent {
-                    code: KeyCode::Char('m'),
+                    code: KeyCode::Char('c'),
+                    modifiers,
                     ..
-                } => self.move_files(),
+                } if modifiers.contains(KeyModifiers::CONTROL) => {
+                    if let Some(command) = self.launcher.cancel() {
+                        self.log.warn(&command, "Cancelled");
+                    }
+                }
                 KeyEvent {
                     code: KeyCode::Char('c'),
                     ..
                 } => self.copy_files(),
+                KeyEvent {
+                    code: KeyCode::Char('y'),
+                    ..
+                } => self.clip(ClipMode::Yank),
+                KeyEvent {
+                    code: KeyCode::Char('x'),
+                    modifiers: KeyModifiers::NONE,
+                    ..
+                } => self.clip(ClipMode::Cut),
+                KeyEvent {
+                    code: KeyCode::Char('p'),
+                    modifiers,
+                    ..
+                } if modifiers.contains(KeyModifiers::CONTROL) => self.paste(PasteMode::Hardlink),
+                KeyEvent {
+                    code: KeyCode::Char('p'),
+                    ..
+                } => self.paste(PasteMode::Files),
+                KeyEvent {
+                    code: KeyCode::Char('P'),
+                    ..
+                } => self.paste(PasteMode::Symlink),
+                KeyEvent {
+                    code: KeyCode::Char('n'),
+                    ..
+                } => self.link_files(PasteMode::Symlink),
+                KeyEvent {
+                    code: KeyCode::Char('N'),
+                    ..
+                } => self.link_files(PasteMode::Hardlink),
+                KeyEvent {
+                    code: KeyCode::Char('f'),
+                    modifiers,
+                    ..
+                } if !modifiers.contains(KeyModifiers::CONTROL) => self.follow_link(),
                 KeyEvent {
                     code: KeyCode::Char('d'),
                     ..
@@ -473,38 +1557,444 @@ impl App {
                     code: KeyCode::Char('h'),
                     ..
                 } => self.help = !self.help,
+                KeyEvent {
+                    code: KeyCode::Char('t'),
+                    modifiers: KeyModifiers::NONE,
+                    ..
+                } => self.new_tab(),
+                KeyEvent {
+                    code: KeyCode::Char('w'),
+                    ..
+                } => self.close_tab(),
+                KeyEvent {
+                    code: KeyCode::Char('['),
+                    ..
+                } => self.switch_tab(self.tab.checked_sub(1).unwrap_or(self.tab_count() - 1)),
+                KeyEvent {
+                    code: KeyCode::Char(']'),
+                    ..
+                } => self.switch_tab((self.tab + 1) % self.tab_count()),
+                KeyEvent {
+                    code: KeyCode::Char(c @ '1'..='9'),
+                    ..
+                } => self.switch_tab(c as usize - '1' as usize),
                 KeyEvent {
                     code: KeyCode::Char('t'),
                     modifiers,
                     ..
-                } => {
-                    if modifiers.contains(KeyModifiers::CONTROL) {
-                        self.notes.creat_file.is_active = !self.notes.creat_file.is_active
-                    }
+                } if modifiers.contains(KeyModifiers::CONTROL) => {
+                    self.notes.creat_file.is_active = !self.notes.creat_file.is_active
                 }
                 KeyEvent {
                     code: KeyCode::Char('f'),
                     modifiers,
                     ..
+                } if modifiers.contains(KeyModifiers::CONTROL) => {
+                    self.notes.create_folder.is_active = !self.notes.create_folder.is_active
+                }
+
+                KeyEvent {
+                    code: KeyCode::Backspace,
+                    ..
                 } => {
-                    if modifiers.contains(KeyModifiers::CONTROL) {
-                        self.notes.create_folder.is_active = !self.notes.create_folder.is_active
+                    self.notes.selected_items.clear();
+                }
+                _ => {}
+            }
+        }
+    }
+
+    fn handle_bookmark_menu_key(&mut self, key_event: KeyEvent) {
+        let marks = self.bookmark_keys();
+        let Some(menu) = &mut self.bookmark_menu else {
+            return;
+        };
+        let selected = menu.state.selected().and_then(|i| marks.get(i)).copied();
+
+        if let Some(name) = &mut menu.rename {
+            match key_event.code {
+                KeyCode::Backspace => {
+                    name.pop();
+                }
+                KeyCode::Char(c) => name.push(c),
+                KeyCode::Enter => {
+                    if let Some(key) = selected {
+                        self.bookmarks.rename(key, name);
+                        self.save_bookmarks();
+                    }
+                    if let Some(menu) = &mut self.bookmark_menu {
+                        menu.rename = None;
                     }
                 }
+                KeyCode::Esc => menu.rename = None,
+                _ => {}
+            }
+            return;
+        }
+
+        match key_event.code {
+            KeyCode::Up => cursor_up(&mut menu.state, marks.len()),
+            KeyCode::Down => cursor_down(&mut menu.state, marks.len()),
+            KeyCode::Enter => {
+                self.bookmark_menu = None;
+                if let Some(key) = selected {
+                    self.jump_to_mark(key);
+                }
+            }
+            KeyCode::Char('r') => match selected.and_then(|key| self.bookmarks.get(key)) {
+                Some(mark) => menu.rename = Some(mark.name.clone()),
+                None => self.log.warn("bookmark", "Built-in marks can't be renamed"),
+            },
+            KeyCode::Char('d') => match selected {
+                Some(key) if self.bookmarks.get(key).is_some() => {
+                    self.bookmarks.remove(key);
+                    self.save_bookmarks();
+                    if let Some(menu) = &mut self.bookmark_menu {
+                        let last = marks.len().saturating_sub(2);
+                        menu.state
+                            .select(menu.state.selected().map(|i| i.min(last)));
+                    }
+                }
+                _ => self.log.warn("bookmark", "Built-in marks can't be deleted"),
+            },
+            KeyCode::Esc | KeyCode::Char('b') | KeyCode::Char('q') => self.bookmark_menu = None,
+            _ => {}
+        }
+    }
+
+    fn exit(&mut self) {
+        self.exit = true;
+        self.cd_on_exit = true;
+    }
+
+    /// Quits without handing the directory to `--choosedir`/`--print-last-dir`.
+    fn exit_without_cd(&mut self) {
+        self.exit = true;
+        self.cd_on_exit = false;
+    }
+
+    fn last_dir(&self) -> &Path {
+        self.notes.dir()
+    }
+
+    /// Number of open tabs, the current one included.
+    fn tab_count(&self) -> usize {
+        self.tabs.len() + 1
+    }
+
+    /// Opens a tab on the current directory, right after the current tab.
+    fn new_tab(&mut self) {
+        match FileList::open(self.last_dir(), self.notes.show_hidden, self.notes.sort) {
+            Ok(list) => {
+                self.tabs.insert(self.tab, list);
+                self.switch_tab(self.tab + 1);
+            }
+            Err(err) => self.log.error("new tab", err),
+        }
+    }
+
+    fn close_tab(&mut self) {
+        if self.tabs.is_empty() {
+            self.log.warn("close tab", "Can't close the last tab");
+            return;
+        }
+
+        // the tab to the right takes its place, or the one to the left at the end
+        let list = if self.tab < self.tabs.len() {
+            self.tabs.remove(self.tab)
+        } else {
+            self.tab -= 1;
+            self.tabs.remove(self.tab)
+        };
+        let mut closed = std::mem::replace(&mut self.notes, list);
+        self.notes.selected_items = std::mem::take(&mut closed.selected_items);
+        self.tab_changed();
+    }
+
+    fn switch_tab(&mut self, to: usize) {
+        if to == self.tab || to >= self.tab_count() {
+            return;
+        }
+
+        let list = self.tabs.remove(if to > self.tab { to - 1 } else { to });
+        let mut previous = std::mem::replace(&mut self.notes, list);
+        // the selection is shared, select in one tab and move or copy into another
+        self.notes.selected_items = std::mem::take(&mut previous.selected_items);
+        self.tabs.insert(
+            if to > self.tab {
+                self.tab
+            } else {
+                self.tab - 1
+            },
+            previous,
+        );
+        self.tab = to;
+        self.tab_changed();
+    }
+
+    /// Splits the view into two listings side by side, or back into one.
+    fn toggle_dual_pane(&mut self) {
+        if self.other_pane.take().is_some() {
+            return;
+        }
+        self.miller = false;
+
+        match FileList::open(self.last_dir(), self.notes.show_hidden, self.notes.sort) {
+            Ok(list) => {
+                self.other_pane = Some(OtherPane {
+                    list,
+                    on_right: true,
+                });
+                self.selected_widget.focus(Pane::List);
+            }
+            Err(err) => self.log.error("dual pane", err),
+        }
+    }
+
+    /// Makes the other pane the current one, each keeps its own selection.
+    fn switch_pane(&mut self) {
+        let Some(other) = &mut self.other_pane else {
+            return;
+        };
+
+        std::mem::swap(&mut self.notes, &mut other.list);
+        other.on_right = !other.on_right;
+        self.tab_changed();
+    }
+
+    /// Exchanges the directories shown in the two panes.
+    fn swap_panes(&mut self) {
+        let Some(other) = &mut self.other_pane else {
+            return;
+        };
+
+        std::mem::swap(&mut self.notes, &mut other.list);
+        self.tab_changed();
+    }
+
+    /// Shows the current directory in the other pane too.
+    fn sync_panes(&mut self) {
+        let Some(other) = &self.other_pane else {
+            return;
+        };
+
+        match FileList::open(self.last_dir(), self.notes.show_hidden, self.notes.sort) {
+            Ok(list) => {
+                self.other_pane = Some(OtherPane {
+                    list,
+                    on_right: other.on_right,
+                })
+            }
+            Err(err) => self.log.error("sync panes", err),
+        }
+    }
+
+    fn update_goto_candidates(&mut self) {
+        let Some(prompt) = &mut self.goto_prompt else {
+            return;
+        };
+
+        prompt.candidates = completion::candidates(&prompt.user_input, self.notes.dir());
+        prompt.state.select(None);
+    }
+
+    /// Tab at the `g` prompt: completes as far as all candidates agree, then
+    /// cycles through them.
+    fn complete_goto(&mut self) {
+        let Some(prompt) = &mut self.goto_prompt else {
+            return;
+        };
+        if prompt.candidates.is_empty() {
+            return;
+        }
+
+        let common = completion::common_prefix(&prompt.candidates);
+        if prompt.state.selected().is_none() && common.len() > prompt.user_input.len() {
+            prompt.user_input = common;
+            self.update_goto_candidates();
+            return;
+        }
+
+        cursor_down(&mut prompt.state, prompt.candidates.len());
+        let i = prompt.state.selected().unwrap_or(0);
+        prompt.user_input = prompt.candidates[i].clone();
+    }
+
+    /// Goes to the directory typed at the `g` prompt, or to the file's directory
+    /// with the cursor on it.
+    fn go_to_input(&mut self, input: &str) {
+        if input.is_empty() {
+            return;
+        }
+
+        let path = completion::expand(input, self.notes.dir());
+        let path = match fs::canonicalize(&path) {
+            Ok(path) => path,
+            Err(err) => {
+                self.log.error("go to", format!("{}: {}", input, err));
+                return;
+            }
+        };
+
+        if path.is_dir() {
+            self.notes.go_to(&path);
+        } else if let (Some(parent), Some(name)) = (path.parent(), path.file_name()) {
+            self.notes.go_to(parent);
+            if !self.notes.reveal_name(&name.to_string_lossy()) {
+                self.log
+                    .warn("go to", format!("{}: Not in the listing", path.display()));
+            }
+        }
+    }
+
+    /// Lists the directories matching what was typed at the `z` prompt.
+    fn update_jump_matches(&mut self) {
+        let Some(prompt) = &mut self.jump_prompt else {
+            return;
+        };
 
-                KeyEvent {
-                    code: KeyCode::Backspace,
-                    ..
-                } => {
-                    self.notes.selected_items.clear();
-                }
-                _ => {}
+        prompt.matches = self.frecency.query(&prompt.user_input, self.notes.dir());
+        prompt
+            .state
+            .select((!prompt.matches.is_empty()).then_some(0));
+    }
+
+    fn set_bookmark(&mut self, key: char) {
+        let dir = self.last_dir().to_path_buf();
+        match self.bookmarks.set(key, &dir) {
+            Ok(()) => {
+                self.log
+                    .info("bookmark", format!("{} = {}", key, dir.display()));
+                self.save_bookmarks();
             }
+            Err(err) => self.log.warn("bookmark", err),
         }
     }
 
-    fn exit(&mut self) {
-        self.exit = true;
+    fn jump_to_mark(&mut self, key: char) {
+        let Some(dir) = self.mark_path(key) else {
+            self.log.warn("bookmark", format!("No mark `{}`", key));
+            return;
+        };
+
+        if dir.is_dir() {
+            self.notes.go_to(&dir);
+        } else {
+            self.log
+                .error("bookmark", format!("{}: Not a directory", dir.display()));
+        }
+    }
+
+    /// `Y` followed by `p`, `n`, `d` or `c`: copies the selected entry's
+    /// path, name, directory or contents to the system clipboard.
+    fn copy_to_system(&mut self, key: char) {
+        let selected = self.selected_path().map(PathBuf::from);
+        let (what, text) = match (key, &selected) {
+            ('d', _) => ("directory", Ok(self.notes.dir().display().to_string())),
+            ('p', Some(path)) => ("path", Ok(path.display().to_string())),
+            ('n', Some(path)) => (
+                "name",
+                Ok(path
+                    .file_name()
+                    .map(|name| name.to_string_lossy().to_string())
+                    .unwrap_or_default()),
+            ),
+            ('c', Some(path)) => ("contents", preview::read_text(path)),
+            ('p' | 'n' | 'c', None) => {
+                self.log.warn("copy", "Nothing selected");
+                return;
+            }
+            _ => {
+                self.log.warn(
+                    "copy",
+                    format!(
+                        "Y{}: expected p(ath), n(ame), d(irectory) or c(ontents)",
+                        key
+                    ),
+                );
+                return;
+            }
+        };
+
+        let text = match text {
+            Ok(text) => text,
+            Err(err) => {
+                self.log.error("copy", err);
+                return;
+            }
+        };
+        match system_clipboard::copy(&text, self.copy_method) {
+            Ok(method) => {
+                let shown = match text.lines().count() {
+                    0 | 1 => text.clone(),
+                    lines => format!("{} lines", lines),
+                };
+                self.log.info(
+                    "copy",
+                    format!("Copied {} via {}: {}", what, method.name(), shown),
+                );
+            }
+            Err(err) => self
+                .log
+                .error("copy", format!("{} to clipboard: {}", what, err)),
+        }
+    }
+
+    /// Where a built-in or user mark points.
+    fn mark_path(&self, key: char) -> Option<PathBuf> {
+        match key {
+            '~' => std::env::var_os("HOME").map(PathBuf::from),
+            '/' => Some(PathBuf::from("/")),
+            '\'' => self.notes.back.last().cloned(),
+            _ => self.bookmarks.get(key).map(|mark| mark.path.clone()),
+        }
+    }
+
+    /// Keys of the marks listed in the bookmark manager, built-in ones first.
+    fn bookmark_keys(&self) -> Vec<char> {
+        Bookmarks::BUILT_IN
+            .into_iter()
+            .chain(self.bookmarks.marks.iter().map(|mark| mark.key))
+            .collect()
+    }
+
+    fn save_bookmarks(&mut self) {
+        if let Some(path) = Bookmarks::default_path()
+            && let Err(err) = self.bookmarks.save(&path)
+        {
+            self.log.error(
+                "bookmark",
+                format!("cannot save {}: {}", path.display(), err),
+            );
+        }
+    }
+
+    /// Where `^x` and `c` put the selection: the other pane's directory in the
+    /// dual-pane layout, the current one otherwise.
+    fn target_dir(&self) -> PathBuf {
+        match &self.other_pane {
+            Some(other) => other.list.dir().to_path_buf(),
+            None => self.last_dir().to_path_buf(),
+        }
+    }
+
+    fn tab_changed(&mut self) {
+        // only the current tab is watched, the others may be out of date
+        self.notes.reload();
+        self.selected_widget.file_preview.scroll = Scroll::default();
+        self.last_click = None;
+    }
+
+    fn write_last_dir(&self, args: &cli::Args) -> io::Result<()> {
+        let dir = self.last_dir().to_string_lossy().to_string();
+
+        if let Some(file) = &args.choosedir {
+            fs::write(file, &dir)?;
+        }
+        if args.print_last_dir {
+            println!("{}", dir);
+        }
+        Ok(())
     }
 
     fn previous(&mut self) {
@@ -530,10 +2020,8 @@ impl App {
             && self.selected_widget.file_info.scroll.y > 0
         {
             self.selected_widget.file_info.scroll.y -= 1;
-        } else if self.selected_widget.file_selection.is_active
-            && self.selected_widget.file_selection.scroll.y > 0
-        {
-            self.selected_widget.file_selection.scroll.y -= 1;
+        } else if self.selected_widget.file_selection.is_active {
+            self.move_selection_cursor(true);
         }
     }
 
@@ -556,40 +2044,177 @@ impl App {
         } else if self.selected_widget.file_info.is_active {
             self.selected_widget.file_info.scroll.y += 1;
         } else if self.selected_widget.file_selection.is_active {
-            self.selected_widget.file_selection.scroll.y += 1;
+            self.move_selection_cursor(false);
         }
     }
 
     fn open_via_app(&mut self) {
-        let mut selection = String::new();
-        selection.push_str(
-            &self
+        let Some(item) = self
+            .notes
+            .state
+            .selected()
+            .and_then(|index| self.notes.items.get(index))
+            .cloned()
+        else {
+            return;
+        };
+
+        match self
+            .openers
+            .iter()
+            .position(|opener| opener.matches(Path::new(&item)))
+        {
+            Some(index) => self.run_opener(index, &item),
+            None => self.log.warn("open", format!("No opener matches {}", item)),
+        }
+    }
+
+    fn show_open_with(&mut self) {
+        let Some(item) = self
+            .notes
+            .state
+            .selected()
+            .and_then(|index| self.notes.items.get(index))
+        else {
+            return;
+        };
+
+        let openers: Vec<usize> = self
+            .openers
+            .iter()
+            .enumerate()
+            .filter(|(_, opener)| opener.matches(Path::new(item)))
+            .map(|(index, _)| index)
+            .collect();
+
+        if openers.is_empty() {
+            self.log.warn("open", format!("No opener matches {}", item));
+            return;
+        }
+
+        self.open_with = Some(OpenWith {
+            path: item.clone(),
+            openers,
+            state: ListState::default().with_selected(Some(0)),
+        });
+    }
+
+    fn run_opener(&mut self, index: usize, item: &str) {
+        let command =
+            self.openers[index].command_for(item, self.last_dir(), &self.notes.selected_items);
+
+        match self.openers[index].mode {
+            LaunchMode::Terminal => {
+                let process = launcher::shell_command(&command, self.last_dir());
+                self.launcher.foreground = Some((command, process));
+            }
+            LaunchMode::Detached => {
+                let dir = self.last_dir().to_path_buf();
+                if let Err(err) = self.launcher.spawn_detached(&command, &dir) {
+                    self.log.error(&command, err.to_string());
+                }
+            }
+        }
+    }
+
+    /// Runs what was typed at the `:` prompt, in the background if it ends with `&`.
+    fn run_command(&mut self) {
+        let input = std::mem::take(&mut self.command_prompt.user_input);
+        self.command_prompt.is_active = false;
+
+        let (input, background) = match input.trim().strip_suffix('&') {
+            Some(input) => (input.trim().to_string(), true),
+            None => (input.trim().to_string(), false),
+        };
+        if input.is_empty() {
+            return;
+        }
+
+        let file = self
+            .notes
+            .state
+            .selected()
+            .and_then(|index| self.notes.items.get(index))
+            .cloned()
+            .unwrap_or_default();
+        let dir = self.last_dir().to_path_buf();
+        let command = opener::expand_placeholders(&input, &file, &dir, &self.notes.selected_items);
+
+        // never on the UI thread, a slow command would freeze drawing
+        if let Err(err) = self.launcher.spawn_captured(&command, &dir, !background) {
+            self.log.error(&command, err.to_string());
+        }
+    }
+
+    fn show_command_output(
+        &mut self,
+        command: String,
+        mut text: String,
+        status: io::Result<ExitStatus>,
+    ) {
+        self.log_status(&command, &status);
+        match status {
+            Ok(status) => text.push_str(&format!("\n[{}]", status)),
+            Err(err) => text.push_str(&err.to_string()),
+        }
+
+        self.command_output = Some(CommandOutput {
+            command,
+            text,
+            scroll: Scroll::default(),
+        });
+    }
+
+    /// Confirms the selection (or the entry under the cursor) in `--pick` mode.
+    fn pick(&mut self) {
+        let Some(picker) = &mut self.picker else {
+            return;
+        };
+
+        let picked: Vec<String> = if self.notes.selected_items.is_empty() {
+            let Some(item) = self
                 .notes
-                .items
-                .get(self.notes.state.selected().unwrap())
-                .unwrap()
-                .to_string(),
-        );
+                .state
+                .selected()
+                .and_then(|index| self.notes.items.get(index))
+            else {
+                return;
+            };
+
+            if picker.accepts(item) {
+                vec![item.clone()]
+            } else if Path::new(item).is_dir() {
+                self.notes.dir_next();
+                return;
+            } else {
+                self.log.warn("pick", format!("{} can't be picked", item));
+                return;
+            }
+        } else {
+            self.notes
+                .selected_items
+                .iter()
+                .filter(|item| picker.accepts(item))
+                .cloned()
+                .collect()
+        };
 
-        let output = Command::new("xdg-open")
-            .arg(selection)
-            .output()
-            .expect("failed to execute process");
+        if picked.is_empty() {
+            self.log
+                .warn("pick", "None of the selected files can be picked");
+            return;
+        }
 
-        let status = output.status;
-        let stderr = String::from_utf8_lossy(&output.stderr);
-        self.error_output.push(format!("{}, {}", status, stderr));
+        picker.picked = Some(picked);
+        self.exit = true;
     }
 
-    fn select_files(self: &mut Self) {
+    fn select_files(&mut self) {
+        // nothing to select in an empty directory
+        let Some(selected_file) = self.selected_path() else {
+            return;
+        };
         let selection = &mut self.notes.selected_items;
-        let selected_file = self
-            .notes
-            .items
-            .get(self.notes.state.selected().unwrap())
-            .unwrap()
-            .as_str()
-            .to_string();
 
         if selection.contains(&selected_file) {
             selection.retain(|item| item != &selected_file);
@@ -598,92 +2223,541 @@ impl App {
         }
     }
 
-    fn move_files(self: &mut Self) {
-        let selection = &self.notes.selected_items;
+    /// Moves the selection panel's cursor, stopping at the ends.
+    fn move_selection_cursor(&mut self, up: bool) {
+        let last = self.notes.selected_items.len().saturating_sub(1);
+        let panel = &mut self.selected_widget.file_selection;
+        panel.cursor = if up {
+            panel.cursor.saturating_sub(1)
+        } else {
+            (panel.cursor + 1).min(last)
+        };
+    }
+
+    /// Removes the entry under the selection panel's cursor from the selection.
+    fn unselect_at_cursor(&mut self) {
+        let cursor = self.selected_widget.file_selection.cursor;
+        let Some(item) = selection::grouped(&self.notes.selected_items)
+            .into_iter()
+            .flat_map(|(_, items)| items)
+            .nth(cursor)
+            .cloned()
+        else {
+            return;
+        };
+
+        self.notes
+            .selected_items
+            .retain(|selected| selected != &item);
+        let last = self.notes.selected_items.len().saturating_sub(1);
+        self.selected_widget.file_selection.cursor = cursor.min(last);
+    }
+
+    /// Starts visual mode at the cursor, or ends it keeping the range selected.
+    fn toggle_visual(&mut self) {
+        if self.visual.take().is_some() {
+            return;
+        }
+        let Some(anchor) = self.notes.state.selected() else {
+            return;
+        };
+
+        self.visual = Some(VisualRange {
+            dir: self.notes.dir().to_path_buf(),
+            anchor,
+            before: self.notes.selected_items.clone(),
+            applied: self.notes.selected_items.clone(),
+        });
+    }
+
+    /// Selects the range between the anchor and the cursor. Visual mode
+    /// ends when the directory changes or something else touched the
+    /// selection, like Space or moving the selected files.
+    fn update_visual(&mut self) {
+        let Some(visual) = &mut self.visual else {
+            return;
+        };
+        if visual.dir != self.notes.dir() || visual.applied != self.notes.selected_items {
+            self.visual = None;
+            return;
+        }
+        let Some(cursor) = self.notes.state.selected() else {
+            return;
+        };
 
-        for item in selection {
-            let output = Command::new("mv")
-                .arg(item)
-                .arg(self.notes.path.to_str().unwrap())
-                .output()
-                .expect("failed to execute process");
+        let (start, end) = (visual.anchor.min(cursor), visual.anchor.max(cursor));
+        let mut selection = visual.before.clone();
+        for item in self.notes.items.iter().take(end + 1).skip(start) {
+            if !selection.contains(item) {
+                selection.push(item.clone());
+            }
+        }
+        visual.applied = selection.clone();
+        self.notes.selected_items = selection;
+    }
+
+    fn select_pattern(&mut self, input: &str, select: bool) {
+        let pattern = match NamePattern::parse(input) {
+            Ok(pattern) => pattern,
+            Err(err) => {
+                self.log.error("select", err);
+                return;
+            }
+        };
+
+        let changed = self.notes.select_matching(&pattern, select);
+        let action = if select { "Selected" } else { "Deselected" };
+        self.log.info(
+            "select",
+            format!("{} {} entries matching {}", action, changed, input.trim()),
+        );
+    }
+
+    fn move_files(&mut self) {
+        let selection = self.notes.selected_items.clone();
+        let target = self.target_dir();
 
-            let status = output.status;
-            let stderr = String::from_utf8_lossy(&output.stderr);
-            self.error_output.push(format!("{}, {}", status, stderr));
+        for item in &selection {
+            let output = Command::new("mv").arg(item).arg(&target).output();
+            self.log_output("move", item, output);
         }
 
         self.notes.update();
         self.notes.selected_items.clear();
+        if let Some(other) = &mut self.other_pane {
+            other.list.reload();
+        }
     }
 
-    fn copy_files(self: &mut Self) {
-        let selection = &self.notes.selected_items;
-
-        for item in selection {
-            let output = Command::new("cp")
-                .arg(item)
-                .arg(self.notes.path.to_str().unwrap())
-                .output()
-                .expect("failed to execute process");
+    fn copy_files(&mut self) {
+        let selection = self.notes.selected_items.clone();
+        let target = self.target_dir();
 
-            let status = output.status;
-            let stderr = String::from_utf8_lossy(&output.stderr);
-            self.error_output.push(format!("{}, {}", status, stderr));
+        for item in &selection {
+            let output = Command::new("cp").arg(item).arg(&target).output();
+            self.log_output("copy", item, output);
         }
 
         self.notes.update();
         self.notes.selected_items.clear();
+        if let Some(other) = &mut self.other_pane {
+            other.list.reload();
+        }
+    }
+
+    /// Links the selected files into the target directory, as symlinks or
+    /// hardlinks.
+    fn link_files(&mut self, mode: PasteMode) {
+        let selection = self.notes.selected_items.clone();
+        let target = self.target_dir();
+
+        for item in &selection {
+            let Some(name) = Path::new(item).file_name() else {
+                continue;
+            };
+            let destination = clipboard::free_destination(&target, &name.to_string_lossy());
+            self.make_link(item, &destination, mode);
+        }
+
+        self.notes.reload();
+        self.notes.selected_items.clear();
+        if let Some(other) = &mut self.other_pane {
+            other.list.reload();
+        }
+    }
+
+    /// Creates a symlink or hardlink to `item` at `destination`.
+    fn make_link(&mut self, item: &str, destination: &Path, mode: PasteMode) {
+        let result = match mode {
+            PasteMode::Hardlink => fs::hard_link(item, destination),
+            _ => std::os::unix::fs::symlink(item, destination),
+        };
+        let (action, arrow) = match mode {
+            PasteMode::Hardlink => ("hardlink", "="),
+            _ => ("symlink", "->"),
+        };
+
+        match result {
+            Ok(()) => self.log.info(
+                action,
+                format!("{} {} {}", destination.display(), arrow, item),
+            ),
+            Err(err) => self.log.error(action, format!("{}: {}", item, err)),
+        }
+    }
+
+    /// Goes to where the symlink under the cursor really points, with the
+    /// cursor on the target.
+    fn follow_link(&mut self) {
+        let Some(item) = self.selected_path() else {
+            return;
+        };
+        let Ok(target) = fs::read_link(&item) else {
+            self.log.warn("follow", format!("{}: Not a symlink", item));
+            return;
+        };
+        let real = match fs::canonicalize(&item) {
+            Ok(real) => real,
+            Err(err) => {
+                self.log.error(
+                    "follow",
+                    format!("{} -> {}: {}", item, target.display(), err),
+                );
+                return;
+            }
+        };
+
+        let parent = real.parent().unwrap_or(Path::new("/"));
+        let name = real
+            .file_name()
+            .map(|name| name.to_string_lossy().to_string())
+            .unwrap_or_default();
+        self.notes.go_to(parent);
+        // the target may be hidden
+        self.notes.reveal_name(&name);
     }
 
-    fn delete_files(self: &mut Self) {
-        let selection = &self.notes.selected_items;
+    fn delete_files(&mut self) {
+        let selection = self.notes.selected_items.clone();
 
-        for item in selection {
+        for item in &selection {
             let item_as_path = PathBuf::from(&item);
 
             if item_as_path.is_dir() {
-                let output = Command::new("rm")
-                    .arg("-rf")
-                    .arg(item)
-                    .output()
-                    .expect("failed to execute process");
-
-                let status = output.status;
-                let stderr = String::from_utf8_lossy(&output.stderr);
-                self.error_output.push(format!("{}, {}", status, stderr));
+                let output = Command::new("rm").arg("-rf").arg(item).output();
+                self.log_output("delete", item, output);
             } else {
-                let output = Command::new("rm")
-                    .arg(item)
-                    .output()
-                    .expect("failed to execute process");
-
-                let status = output.status;
-                let stderr = String::from_utf8_lossy(&output.stderr);
-                self.error_output.push(format!("{}, {}", status, stderr));
+                let output = Command::new("rm").arg(item).output();
+                self.log_output("delete", item, output);
+            }
+        }
+
+        self.notes.update();
+        self.notes.selected_items.clear();
+    }
+
+    /// `y` and `x`: puts the selection, or the entry under the cursor, on the clipboard.
+    fn clip(&mut self, mode: ClipMode) {
+        let items = if self.notes.selected_items.is_empty() {
+            match self.selected_path() {
+                Some(item) => vec![item],
+                None => return,
+            }
+        } else {
+            std::mem::take(&mut self.notes.selected_items)
+        };
+
+        let action = match mode {
+            ClipMode::Yank => "yank",
+            ClipMode::Cut => "cut",
+        };
+        self.log.info(action, format!("{} item(s)", items.len()));
+        self.clipboard = Some(Clipboard { mode, items });
+    }
+
+    /// Puts the clipboard into the current directory, never overwriting anything.
+    fn paste(&mut self, mode: PasteMode) {
+        let Some(clipboard) = self.clipboard.take() else {
+            self.log.warn("paste", "Nothing yanked or cut");
+            return;
+        };
+        let dir = self.last_dir().to_path_buf();
+        let moving = clipboard.mode == ClipMode::Cut && mode == PasteMode::Files;
+
+        for item in &clipboard.items {
+            let path = Path::new(item);
+            let Some(name) = path.file_name() else {
+                continue;
+            };
+            if moving && path.parent() == Some(dir.as_path()) {
+                continue;
+            }
+            let destination = clipboard::free_destination(&dir, &name.to_string_lossy());
+
+            match mode {
+                PasteMode::Files if moving => {
+                    let output = Command::new("mv")
+                        .arg("--")
+                        .arg(item)
+                        .arg(&destination)
+                        .output();
+                    self.log_output("move", item, output);
+                }
+                PasteMode::Files => {
+                    let output = Command::new("cp")
+                        .arg("-R")
+                        .arg("--")
+                        .arg(item)
+                        .arg(&destination)
+                        .output();
+                    self.log_output("copy", item, output);
+                }
+                PasteMode::Symlink | PasteMode::Hardlink => {
+                    self.make_link(item, &destination, mode)
+                }
+            }
+        }
+
+        // moved files are gone from where they were, anything else can be pasted again
+        if !moving {
+            self.clipboard = Some(clipboard);
+        }
+
+        self.notes.reload();
+        if let Some(other) = &mut self.other_pane {
+            other.list.reload();
+        }
+    }
+
+    /// Logs how a file operation on `item` went.
+    fn log_output(&mut self, action: &str, item: &str, output: io::Result<Output>) {
+        match output {
+            Ok(output) if output.status.success() => self.log.info(action, item),
+            Ok(output) => self.log.error(
+                action,
+                format!(
+                    "{}: {}",
+                    item,
+                    String::from_utf8_lossy(&output.stderr).trim()
+                ),
+            ),
+            Err(err) => self.log.error(action, format!("{}: {}", item, err)),
+        }
+    }
+
+    /// Logs how a program exited, failures as errors.
+    fn log_status(&mut self, action: &str, status: &io::Result<ExitStatus>) {
+        match status {
+            Ok(status) if status.success() => self.log.info(action, status.to_string()),
+            Ok(status) => self.log.error(action, status.to_string()),
+            Err(err) => self.log.error(action, err.to_string()),
+        }
+    }
+
+    /// Icon and name of an entry, coloured by `LS_COLORS`.
+    fn entry_spans(&self, item: &str) -> Vec<Span<'static>> {
+        let path = Path::new(item);
+        let kind = EntryKind::of(path);
+        let style = self.ls_colors.style_for(path, kind);
+
+        let mut name = item.split('/').next_back().unwrap_or("Error").to_string();
+        // links show where they point, `name -> target/` for one to a directory
+        let mut target = match kind {
+            EntryKind::Symlink | EntryKind::BrokenSymlink => fs::read_link(path)
+                .map(|target| format!(" -> {}", target.display()))
+                .ok(),
+            _ => None,
+        };
+        let suffix = target.as_mut().unwrap_or(&mut name);
+        if kind != EntryKind::BrokenSymlink && path.is_dir() {
+            suffix.push('/');
+        }
+
+        // entries that can't be picked are dimmed in `--pick` mode
+        let style = match &self.picker {
+            Some(picker) if !picker.accepts(item) && kind != EntryKind::Dir => {
+                style.add_modifier(Modifier::DIM)
             }
+            _ => style,
+        };
+        // and so are files that were cut, until they are pasted
+        let style = match &self.clipboard {
+            Some(clipboard) if clipboard.is_cut(item) => style.add_modifier(Modifier::DIM),
+            _ => style,
+        };
+
+        let mut spans = vec![
+            Span::styled(self.icons.icon_for(path, kind), style),
+            Span::styled(name, style),
+        ];
+        if let Some(target) = target {
+            let target_style = if kind == EntryKind::BrokenSymlink {
+                Style::default()
+                    .fg(Color::Red)
+                    .add_modifier(Modifier::CROSSED_OUT)
+            } else {
+                Style::default().fg(Color::DarkGray)
+            };
+            spans.push(Span::styled(target, target_style));
+        }
+        spans
+    }
+
+    /// Spinner and the latest output line while background jobs are running.
+    fn jobs_line(&self) -> Line<'static> {
+        const SPINNER: [&str; 10] = ["⠋", "⠙", "⠹", "⠸", "⠼", "⠴", "⠦", "⠧", "⠇", "⠏"];
+
+        let Some(job) = self.launcher.jobs.values().next_back() else {
+            return Line::default();
+        };
+
+        let mut text = format!(
+            "{} {} job(s): {}",
+            SPINNER[self.spinner % SPINNER.len()],
+            self.launcher.jobs.len(),
+            job.command
+        );
+        if let Some(line) = job.last_line() {
+            text.push_str(&format!(" > {}", line));
+        }
+
+        Line::from(Span::styled(text, Style::default().fg(Color::Yellow)))
+    }
+
+    fn render_tabs(&self, area: Rect, buf: &mut Buffer) {
+        Tabs::new(self.tab_names())
+            .select(self.tab)
+            .style(Style::default().fg(Color::DarkGray))
+            .highlight_style(Style::default().fg(Color::Cyan).bold())
+            .render(area, buf);
+    }
+
+    fn render_list(&mut self, area: Rect, buf: &mut Buffer) {
+        self.notes.clamp_cursor();
+        let active = self.selected_widget.file_list.is_active;
+        self.areas.list_offset = self.render_file_list(&self.notes, active, area, buf);
+    }
+
+    fn render_other_pane(&mut self, area: Rect, buf: &mut Buffer) {
+        let Some(other) = &mut self.other_pane else {
+            return;
+        };
+        other.list.clamp_cursor();
+
+        if let Some(other) = &self.other_pane {
+            self.render_file_list(&other.list, false, area, buf);
         }
+    }
+
+    /// Two listings side by side above the selection, without preview and info.
+    fn render_dual_pane(&mut self, area: Rect, buf: &mut Buffer) {
+        let layout = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([
+                Constraint::Length(if self.tab_count() > 1 { 1 } else { 0 }),
+                Constraint::Percentage(80),
+                Constraint::Fill(1),
+            ])
+            .split(area);
+
+        let panes = Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints([Constraint::Percentage(50), Constraint::Percentage(50)])
+            .split(layout[1]);
+
+        let (list, other) = match &self.other_pane {
+            Some(other) if !other.on_right => (panes[1], panes[0]),
+            _ => (panes[0], panes[1]),
+        };
+
+        self.areas.tabs = layout[0];
+        self.areas.list = list;
+        self.areas.other_pane = other;
+        self.areas.preview = Rect::default();
+        self.areas.info = Rect::default();
+        self.areas.selection = layout[2];
+
+        self.render_tabs(layout[0], buf);
+        self.render_list(list, buf);
+        self.render_other_pane(other, buf);
+        self.render_selection(layout[2], buf);
+    }
+
+    /// Parent directory, current directory and the preview of the entry under the cursor.
+    fn render_miller(&mut self, area: Rect, buf: &mut Buffer) {
+        let layout = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([
+                Constraint::Length(if self.tab_count() > 1 { 1 } else { 0 }),
+                Constraint::Fill(1),
+            ])
+            .split(area);
+
+        let columns = Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints([
+                Constraint::Percentage(20),
+                Constraint::Percentage(40),
+                Constraint::Percentage(40),
+            ])
+            .split(layout[1]);
+
+        self.areas.tabs = layout[0];
+        self.areas.list = columns[1];
+        self.areas.other_pane = Rect::default();
+        self.areas.preview = columns[2];
+        self.areas.info = Rect::default();
+        self.areas.selection = Rect::default();
+
+        self.render_tabs(layout[0], buf);
+        self.render_parent(columns[0], buf);
+        self.render_list(columns[1], buf);
+        self.render_file_preview(columns[2], buf);
+    }
 
-        self.notes.update();
-        self.notes.selected_items.clear();
+    /// The parent directory with the current one highlighted.
+    fn render_parent(&self, area: Rect, buf: &mut Buffer) {
+        let dir = self.last_dir();
+        let parent = dir.parent();
+
+        let items = parent
+            .map(|parent| self.notes.entries(parent))
+            .unwrap_or_default();
+        let current = items.iter().position(|item| Path::new(item) == dir);
+
+        // only the rows that fit are styled, with the current directory at
+        // the bottom at most
+        let rows = (area.height as usize).saturating_sub(2);
+        let start = current.map_or(0, |current| (current + 1).saturating_sub(rows));
+        let current = current.map(|current| current - start);
+
+        let list = List::new(
+            items
+                .iter()
+                .skip(start)
+                .take(rows)
+                .map(|item| ListItem::new(Line::from(self.entry_spans(item)))),
+        )
+        .highlight_style(Style::default().fg(Color::Blue).bg(Color::White))
+        .block(
+            Block::new()
+                .borders(Borders::ALL)
+                .border_type(BorderType::Rounded)
+                .border_style(Style::default().fg(Color::DarkGray))
+                .title(
+                    parent
+                        .map(|parent| parent.to_string_lossy().to_string())
+                        .unwrap_or_default(),
+                ),
+        );
+
+        let mut state = ListState::default().with_selected(current);
+        ratatui::widgets::StatefulWidget::render(list, area, buf, &mut state);
     }
 
-    fn render_list(&mut self, area: Rect, buf: &mut Buffer) {
-        let selected_item = self.notes.state.selected();
+    /// Draws one directory listing, returns the index of its first visible entry.
+    fn render_file_list(
+        &self,
+        list: &FileList,
+        active: bool,
+        area: Rect,
+        buf: &mut Buffer,
+    ) -> usize {
+        let selected_item = list.state.selected();
 
         let mut path = "";
 
-        if let Some(index) = self.notes.state.selected() {
-            if let Some(item) = self.notes.items.get(index) {
-                if let Some(pos) = item.rfind('/') {
-                    path = &item[..pos]; // take everything up to the last '/'
-                } else {
-                    path = item; // no '/' found, keep as is
-                }
+        if let Some(index) = list.state.selected()
+            && let Some(item) = list.items.get(index)
+        {
+            if let Some(pos) = item.rfind('/') {
+                path = &item[..pos]; // take everything up to the last '/'
+            } else {
+                path = item; // no '/' found, keep as is
             }
         }
 
-        let border_color = if self.selected_widget.file_list.is_active {
+        let border_color = if active {
             Style::default().fg(Color::Blue)
         } else {
             Style::default().fg(Color::DarkGray)
@@ -693,44 +2767,28 @@ impl App {
             .borders(Borders::ALL)
             .border_type(BorderType::Rounded)
             .border_style(border_color)
-            .title_bottom(Line::from(vec![
-                Span::styled("h Help  ", Style::default().fg(Color::Cyan)).bold(),
-                Span::styled("q Quit", Style::default().fg(Color::Red)).bold(),
-            ]))
+            .title_bottom(if self.picker.is_some() {
+                Line::from(vec![
+                    Span::styled("⏎ Pick  ", Style::default().fg(Color::Cyan)).bold(),
+                    Span::styled("q Cancel", Style::default().fg(Color::Red)).bold(),
+                ])
+            } else {
+                Line::from(vec![
+                    Span::styled("h Help  ", Style::default().fg(Color::Cyan)).bold(),
+                    Span::styled("q Quit", Style::default().fg(Color::Red)).bold(),
+                ])
+            })
+            .title_bottom(if active {
+                self.jobs_line().right_aligned()
+            } else {
+                Line::default()
+            })
             .title(Line::from(Span::styled(
                 "📁 File Browser",
                 Style::default().fg(Color::Cyan).bold(),
             )))
             .title(path);
 
-        let mut list_items: Vec<ListItem> = self
-            .notes
-            .items
-            .iter()
-            .enumerate()
-            .map(|(i, note)| {
-                let style = if Some(i) == selected_item {
-                    Style::default().fg(Color::Blue).bg(Color::White)
-                } else if  self.notes.selected_items.contains(note){
-                    Style::default().fg(Color::Blue).bg(Color::DarkGray)
-                }
-                else {
-                    Style::default()
-                };
-                let mut x = note.split('/').last().unwrap_or("Error").to_string();
-                match fs::metadata(note) {
-                    Ok(metadata) => {
-                        if metadata.is_dir() {
-                            x.push('/');
-                        }
-                    }
-                    _ => {}
-                }
-
-                ListItem::new(x).style(style)
-            })
-            .collect();
-
         /*
             if i had no area...
 
@@ -748,40 +2806,50 @@ impl App {
         let rows = area.height as usize;
         let rows = rows.saturating_sub(2);
 
-        if let Some(selected_index) = selected_item {
-            let len = list_items.len();
-
-            if selected_index >= len {
-                self.notes.state.select(Some(0));
-            }
-
-            let x = ((len) as f32 / (rows) as f32).ceil() as i32;
-            let multiples: Vec<i32> = (1..x).map(|i| rows as i32 * i).collect();
+        // the list shows a page of `rows` entries at a time, the one with the
+        // cursor. Only those are styled, that takes a few syscalls per entry.
+        let offset = match selected_item {
+            Some(selected_index) if rows > 0 => selected_index / rows * rows,
+            _ => 0,
+        };
 
-            for value in multiples {
-                if selected_index >= value as usize {
-                    list_items.drain(0..rows as usize);
+        let list_items: Vec<ListItem> = list
+            .items
+            .iter()
+            .enumerate()
+            .skip(offset)
+            .take(rows)
+            .map(|(i, note)| {
+                if Some(i) == selected_item {
+                    let line: String = self
+                        .entry_spans(note)
+                        .iter()
+                        .map(|span| span.content.as_ref())
+                        .collect();
+                    ListItem::new(line).style(Style::default().fg(Color::Blue).bg(Color::White))
+                } else if list.selected_items.contains(note) {
+                    ListItem::new(Line::from(self.entry_spans(note)))
+                        .style(Style::default().bg(Color::DarkGray))
+                } else {
+                    ListItem::new(Line::from(self.entry_spans(note)))
                 }
-            }
-        } else {
-            self.notes.state.select(Some(0));
-        }
+            })
+            .collect();
 
         let list = List::new(list_items).block(block);
         list.render(area, buf);
+        offset
     }
 
     fn render_file_preview(&mut self, area: Rect, buf: &mut Buffer) {
         let entry_name = self.notes.selected_item();
 
-        let mut text = String::new();
-
         let mut path = "";
 
-        if let Some(index) = self.notes.state.selected() {
-            if let Some(item) = self.notes.items.get(index) {
-                path = item.as_str();
-            }
+        if let Some(index) = self.notes.state.selected()
+            && let Some(item) = self.notes.items.get(index)
+        {
+            path = item.as_str();
         }
 
         /*
@@ -792,35 +2860,25 @@ impl App {
         .unwrap()
         .as_str(); */
 
-        if self.notes.is_file && !self.notes.is_dir {
-            let file_content = match fs::read_to_string(&path) {
-                Ok(content) => content,
-                _ => String::from(&path.to_string()),
-            };
+        let scroll = (
+            self.selected_widget.file_preview.scroll.y,
+            self.selected_widget.file_preview.scroll.x,
+        );
 
-            text.push_str(&file_content);
-        } else {
-            let dir_items = self.notes.dir_ls();
-            let mut dir_items: Vec<String> = dir_items
-                .split('\n')
-                .map(|item| {
-                    let mut item = item.to_string();
-                    let mut path = self.notes.selected_item();
-                    path.push_str(format!("/{}", item).as_str());
-
-                    let path_buf = PathBuf::from(path.clone());
-
-                    if path_buf.is_dir() {
-                        item.push('/');
-                        item
-                    } else {
-                        item
-                    }
-                })
-                .collect();
-            dir_items.pop();
-            text.push_str(format!("{}", dir_items.join("\n")).as_str());
-        }
+        let text = match &self.preview.content {
+            Some(PreviewContent::Text(content)) => Text::raw(content.as_str()),
+            Some(PreviewContent::Dir(items)) => {
+                // entries below the pane aren't styled, nothing shows them
+                let lines: Vec<Line> = items
+                    .iter()
+                    .take(scroll.0 as usize + area.height as usize)
+                    .map(|item| Line::from(self.entry_spans(item)))
+                    .collect();
+                Text::from(lines)
+            }
+            None if path.is_empty() => Text::default(),
+            None => Text::raw("Loading…").dark_gray(),
+        };
 
         let border_color = if self.selected_widget.file_preview.is_active {
             Style::default().fg(Color::Cyan)
@@ -828,11 +2886,6 @@ impl App {
             Style::default().fg(Color::DarkGray)
         };
 
-        let scroll = (
-            self.selected_widget.file_preview.scroll.y,
-            self.selected_widget.file_preview.scroll.x,
-        );
-
         let preview = Paragraph::new(text)
             .wrap(Wrap { trim: true })
             .scroll(scroll)
@@ -852,52 +2905,48 @@ impl App {
     fn render_file_info(&mut self, area: Rect, buf: &mut Buffer) {
         //let entry_name = self.notes.selected_item();
 
-        let mut text = Text::raw(self.input.as_str());
+        let mut text = Text::default();
         let mut file_data = String::new();
         if Some(self.notes.state.selected()).is_some() {
             if let Some(index) = self.notes.state.selected() {
                 if let Some(path) = self.notes.items.get(index) {
                     let path_as_path = Path::new(path);
-                    let extension: String;
-                    match path_as_path.extension() {
-                        Some(ext) => extension = ext.to_string_lossy().to_string(),
-                        None => extension = String::from("none"),
+                    let extension = match path_as_path.extension() {
+                        Some(ext) => ext.to_string_lossy().to_string(),
+                        None => String::from("none"),
+                    };
+                    if let Ok(target) = fs::read_link(path) {
+                        let broken = if fs::metadata(path).is_err() {
+                            " (broken)"
+                        } else {
+                            ""
+                        };
+                        file_data = format!("Link to: {}{}\n", target.display(), broken);
                     }
-                    match fs::metadata(path) {
-                        Ok(metadata) => {
-                            self.notes.is_file = metadata.is_file();
-                            self.notes.is_dir = metadata.is_dir();
-                            file_data = format!(
-                                "Extension:{:?}\nSize: {:.2} KiB\nCreated: {}\nModified: {}\n",
-                                extension,
-                                metadata.len() as f64 / 1024.0,
-                                metadata
-                                    .created()
-                                    .ok()
-                                    .and_then(|t| {
-                                        let d = t.duration_since(UNIX_EPOCH).ok()?;
-                                        DateTime::from_timestamp(
-                                            d.as_secs() as i64,
-                                            d.subsec_nanos(),
-                                        )
-                                    })
-                                    .map(|dt| dt.format("%Y-%m-%d %H:%M:%S").to_string())
-                                    .unwrap_or_else(|| "Unavailable".to_string()),
-                                metadata
-                                    .modified()
-                                    .ok()
-                                    .and_then(|t| {
-                                        let d = t.duration_since(UNIX_EPOCH).ok()?;
-                                        DateTime::from_timestamp(
-                                            d.as_secs() as i64,
-                                            d.subsec_nanos(),
-                                        )
-                                    })
-                                    .map(|dt| dt.format("%Y-%m-%d %H:%M:%S").to_string())
-                                    .unwrap_or_else(|| "Unavailable".to_string()),
-                            );
-                        }
-                        _ => {}
+                    if let Ok(metadata) = fs::metadata(path) {
+                        file_data += &format!(
+                            "Extension:{:?}\nSize: {:.2} KiB\nCreated: {}\nModified: {}\n",
+                            extension,
+                            metadata.len() as f64 / 1024.0,
+                            metadata
+                                .created()
+                                .ok()
+                                .and_then(|t| {
+                                    let d = t.duration_since(UNIX_EPOCH).ok()?;
+                                    DateTime::from_timestamp(d.as_secs() as i64, d.subsec_nanos())
+                                })
+                                .map(|dt| dt.format("%Y-%m-%d %H:%M:%S").to_string())
+                                .unwrap_or_else(|| "Unavailable".to_string()),
+                            metadata
+                                .modified()
+                                .ok()
+                                .and_then(|t| {
+                                    let d = t.duration_since(UNIX_EPOCH).ok()?;
+                                    DateTime::from_timestamp(d.as_secs() as i64, d.subsec_nanos())
+                                })
+                                .map(|dt| dt.format("%Y-%m-%d %H:%M:%S").to_string())
+                                .unwrap_or_else(|| "Unavailable".to_string()),
+                        );
                     }
                 } else {
                     file_data = "No Permission for this Folder, PageDown to return".to_string();
@@ -936,64 +2985,123 @@ impl App {
         editor.render(area, buf);
     }
 
+    /// The selection grouped by parent directory, with the size of every
+    /// entry, each group and the whole selection.
     fn render_selection(&mut self, area: Rect, buf: &mut Buffer) {
-        let text = self.notes.selected_items.join(", ");
-        //let text = self.notes.selected_item();
-        //let text = self.error_output.join(", ");
+        let groups = selection::grouped(&self.notes.selected_items);
+        let active = self.selected_widget.file_selection.is_active;
+        let panel = &mut self.selected_widget.file_selection;
+        panel.cursor = panel
+            .cursor
+            .min(self.notes.selected_items.len().saturating_sub(1));
+
+        let width = area.width.saturating_sub(2) as usize;
+        let mut rows: Vec<ListItem> = Vec::new();
+        let mut cursor_row = None;
+        let mut total = 0;
+        let mut index = 0;
+
+        for (parent, items) in &groups {
+            let metadata: Vec<Option<fs::Metadata>> = items
+                .iter()
+                .map(|item| fs::symlink_metadata(item).ok())
+                .collect();
+            let size: u64 = metadata
+                .iter()
+                .flatten()
+                .map(|metadata| metadata.len())
+                .sum();
+            total += size;
+
+            rows.push(ListItem::new(Line::from(vec![
+                Span::styled(
+                    parent.display().to_string(),
+                    Style::default().fg(Color::Blue).bold(),
+                ),
+                Span::styled(
+                    format!(" ({}, {})", items.len(), disk::human_size(size)),
+                    Style::default().fg(Color::DarkGray),
+                ),
+            ])));
+
+            for (item, metadata) in items.iter().zip(&metadata) {
+                if index == panel.cursor {
+                    cursor_row = Some(rows.len());
+                }
+                index += 1;
 
-        /*let text = vec![
-                    self.selected_widget.file_list.is_active.to_string(),
-                    self.selected_widget.file_preview.is_active.to_string(),
-                    self.selected_widget.file_info.is_active.to_string(),
-                ];
-                let test = String::from(text.join("\n"));
+                let mut name = item.split('/').next_back().unwrap_or(item).to_string();
+                let (size, size_style) = match metadata {
+                    Some(metadata) => {
+                        if metadata.is_dir() {
+                            name.push('/');
+                        }
+                        (disk::human_size(metadata.len()), Style::default())
+                    }
+                    // deleted or moved away since it was selected
+                    None => ("gone".to_string(), Style::default().fg(Color::Red)),
+                };
+                let padding = width.saturating_sub(name.chars().count() + size.len() + 2);
 
+                rows.push(ListItem::new(Line::from(vec![
+                    Span::raw(format!("  {}{}", name, " ".repeat(padding))),
+                    Span::styled(size, size_style),
+                ])));
+            }
+        }
 
-        */
+        let title = match self.notes.selected_items.len() {
+            0 => "✅ Selected Files".to_string(),
+            count => format!("✅ Selected Files ({}, {})", count, disk::human_size(total)),
+        };
 
-        let border_color = if self.selected_widget.file_selection.is_active {
+        let border_color = if active {
             Style::default().fg(Color::Cyan)
         } else {
             Style::default().fg(Color::DarkGray)
         };
 
-        let scroll = (
-            self.selected_widget.file_selection.scroll.y,
-            self.selected_widget.file_selection.scroll.x,
-        );
+        let mut bottom = vec![Span::styled(
+            "󰭜 Clear Selected Files",
+            Style::default().fg(Color::Cyan).bold(),
+        )];
+        if active {
+            bottom.push(Span::styled(
+                "  ␣ Remove",
+                Style::default().fg(Color::Cyan).bold(),
+            ));
+        }
 
-        let selection = Paragraph::new(text)
-            .wrap(Wrap { trim: true })
+        let selection = List::new(rows)
+            .highlight_style(Style::default().fg(Color::Blue).bg(Color::White))
             .block(
                 Block::default()
                     .title(Line::from(Span::styled(
-                        "✅ Selected Files",
-                        Style::default().fg(Color::Cyan).bold(),
-                    )))
-                    .title_bottom(Line::from(Span::styled(
-                        "󰭜 Clear Selected Files",
+                        title,
                         Style::default().fg(Color::Cyan).bold(),
                     )))
+                    .title_bottom(Line::from(bottom))
                     .borders(Borders::ALL)
                     .style(border_color),
             )
-            .scroll(scroll)
             .style(Style::default().fg(Color::Cyan));
 
-        selection.render(area, buf);
+        // the cursor only shows while the panel has the focus
+        panel.state.select(cursor_row.filter(|_| active));
+        ratatui::widgets::StatefulWidget::render(selection, area, buf, &mut panel.state);
     }
 
     fn render_help(&mut self, area: Rect, buf: &mut Buffer) {
-        let text = format!(
-            "↑↓ Navigate\n⏎ Open\n␣ Select\nPgUp/PgDn Dir Nav\nm Move\nc Copy\nd Delete\n^f Create Folder\n^t Create File\n󰭜 Clear Selected Files\nq Quit"
-        );
+        let text = "↑↓ Navigate\n⏎ Open\no Open With\n␣ Select\nv Visual Select\na Select All\ni Invert Selection\n+/- Select/Deselect by Pattern\n␣/Del Remove (Selection Pane)\nPgUp/PgDn Dir Nav\n^x Move\nc Copy\nd Delete\ny Yank\nx Cut\np Paste\nP Paste as Symlink\n^p Paste as Hardlink\n^c Cancel Running Command\nn/N Symlink/Hardlink Selection Here\nf Follow Symlink\nYp/Yn/Yd/Yc Copy Path/Name/Dir/Contents\n^f Create Folder\n^t Create File\n󰭜 Clear Selected Files\nt New Tab\nw Close Tab\n[ ] 1-9 Switch Tab\n←→ Up/Down the Tree\nH/L History Back/Forward\nR Recent Directories\nm<key> Set Bookmark\n'<key> Jump to Bookmark\nb Bookmarks\nz Jump to Directory\ng Go to Path\nM Miller Columns\n| Dual Pane\n⇥ Switch Pane\ns Swap Panes\n= Sync Panes\n: Run Command\nS Shell\nl Log\nq Quit\nQ Quit without cd".to_string();
         let mut len_text: Vec<usize> = text.split('\n').map(|string| string.len()).collect();
         len_text.sort();
         let longest_text = len_text[len_text.len() - 1];
 
-        self.error_output.push(format!("{:?}", longest_text));
-        let padding_top = (area.height - text.chars().filter(|&c| c == '\n').count() as u16) / 2;
-        let padding_left = area.width / 2 - (longest_text / 2) as u16;
+        let padding_top = area
+            .height
+            .saturating_sub(text.chars().filter(|&c| c == '\n').count() as u16)
+            / 2;
+        let padding_left = (area.width / 2).saturating_sub((longest_text / 2) as u16);
 
         let test = Paragraph::new(text)
             .block(Block::default().borders(Borders::ALL).padding(Padding {
@@ -1007,9 +3115,468 @@ impl App {
         test.render(area, buf);
     }
 
+    fn render_open_with(&mut self, area: Rect, buf: &mut Buffer) {
+        let Some(menu) = &mut self.open_with else {
+            return;
+        };
+
+        let items: Vec<ListItem> = menu
+            .openers
+            .iter()
+            .map(|&index| ListItem::new(self.openers[index].to_string()))
+            .collect();
+
+        let name = menu.path.split('/').next_back().unwrap_or("").to_string();
+
+        let list = List::new(items)
+            .highlight_style(Style::default().fg(Color::Blue).bg(Color::White))
+            .block(
+                Block::default()
+                    .borders(Borders::ALL)
+                    .border_type(BorderType::Rounded)
+                    .border_style(Style::default().fg(Color::Cyan))
+                    .title(Line::from(vec![
+                        Span::styled(" Open with: ", Style::default().fg(Color::Cyan).bold()),
+                        Span::raw(name),
+                    ]))
+                    .title_bottom(Line::from(vec![
+                        Span::styled("⏎ Open  ", Style::default().fg(Color::Cyan)).bold(),
+                        Span::styled("Esc Close", Style::default().fg(Color::Red)).bold(),
+                    ])),
+            );
+
+        ratatui::widgets::StatefulWidget::render(list, area, buf, &mut menu.state);
+    }
+
+    fn render_goto_prompt(&mut self, area: Rect, buf: &mut Buffer) {
+        let Some(prompt) = &mut self.goto_prompt else {
+            return;
+        };
+
+        let layout = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([Constraint::Length(3), Constraint::Fill(1)])
+            .split(area);
+
+        let input = Paragraph::new(prompt.user_input.to_string()).block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title(vec![Span::from(" Go to path")])
+                .title_bottom(vec![
+                    Span::styled("⏎ Go  ", Style::default().fg(Color::Cyan)).bold(),
+                    Span::styled("⇥ Complete", Style::default().fg(Color::Cyan)).bold(),
+                    Span::raw("  "),
+                    Span::styled("Esc Close", Style::default().fg(Color::Red)).bold(),
+                ]),
+        );
+        input.render(layout[0], buf);
+
+        let items: Vec<ListItem> = prompt
+            .candidates
+            .iter()
+            .map(|candidate| ListItem::new(candidate.as_str()))
+            .collect();
+
+        let list = List::new(items)
+            .highlight_style(Style::default().fg(Color::Blue).bg(Color::White))
+            .block(
+                Block::default()
+                    .borders(Borders::ALL)
+                    .border_type(BorderType::Rounded)
+                    .border_style(Style::default().fg(Color::DarkGray)),
+            );
+
+        ratatui::widgets::StatefulWidget::render(list, layout[1], buf, &mut prompt.state);
+    }
+
+    fn render_jump_prompt(&mut self, area: Rect, buf: &mut Buffer) {
+        let Some(prompt) = &mut self.jump_prompt else {
+            return;
+        };
+
+        let layout = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([Constraint::Length(3), Constraint::Fill(1)])
+            .split(area);
+
+        let input = Paragraph::new(prompt.user_input.to_string()).block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title(vec![Span::from(" Jump to (z)")])
+                .title_bottom(vec![
+                    Span::styled("⏎ Go  ", Style::default().fg(Color::Cyan)).bold(),
+                    Span::styled("⇥ Complete", Style::default().fg(Color::Cyan)).bold(),
+                    Span::raw("  "),
+                    Span::styled("Esc Close", Style::default().fg(Color::Red)).bold(),
+                ]),
+        );
+        input.render(layout[0], buf);
+
+        let items: Vec<ListItem> = prompt
+            .matches
+            .iter()
+            .map(|dir| ListItem::new(dir.to_string_lossy().to_string()))
+            .collect();
+
+        let list = List::new(items)
+            .highlight_style(Style::default().fg(Color::Blue).bg(Color::White))
+            .block(
+                Block::default()
+                    .borders(Borders::ALL)
+                    .border_type(BorderType::Rounded)
+                    .border_style(Style::default().fg(Color::DarkGray)),
+            );
+
+        ratatui::widgets::StatefulWidget::render(list, layout[1], buf, &mut prompt.state);
+    }
+
+    fn render_bookmarks(&mut self, area: Rect, buf: &mut Buffer) {
+        let rows: Vec<(char, String, String)> = self
+            .bookmark_keys()
+            .into_iter()
+            .map(|key| {
+                let name = match key {
+                    '~' => "home".to_string(),
+                    '/' => "root".to_string(),
+                    '\'' => "previous".to_string(),
+                    _ => self
+                        .bookmarks
+                        .get(key)
+                        .map(|mark| mark.name.clone())
+                        .unwrap_or_default(),
+                };
+                let path = self
+                    .mark_path(key)
+                    .map(|path| path.to_string_lossy().to_string())
+                    .unwrap_or_default();
+                (key, name, path)
+            })
+            .collect();
+
+        let Some(menu) = &mut self.bookmark_menu else {
+            return;
+        };
+
+        let width = rows
+            .iter()
+            .map(|(_, name, _)| name.chars().count())
+            .max()
+            .unwrap_or(0);
+        let items: Vec<ListItem> = rows
+            .into_iter()
+            .map(|(key, name, path)| {
+                let style = if Bookmarks::BUILT_IN.contains(&key) {
+                    Style::default().fg(Color::DarkGray)
+                } else {
+                    Style::default()
+                };
+                ListItem::new(Line::from(vec![
+                    Span::styled(
+                        format!("{}  ", key),
+                        Style::default().fg(Color::Cyan).bold(),
+                    ),
+                    Span::styled(format!("{:width$}  ", name, width = width), style),
+                    Span::styled(path, style),
+                ]))
+            })
+            .collect();
+
+        let bottom = match &menu.rename {
+            Some(name) => Line::from(vec![
+                Span::styled("Rename: ", Style::default().fg(Color::Cyan)).bold(),
+                Span::raw(name.clone()),
+                Span::raw("█"),
+            ]),
+            None => Line::from(vec![
+                Span::styled("⏎ Go  ", Style::default().fg(Color::Cyan)).bold(),
+                Span::styled("r Rename  ", Style::default().fg(Color::Cyan)).bold(),
+                Span::styled("d Delete  ", Style::default().fg(Color::Cyan)).bold(),
+                Span::styled("Esc Close", Style::default().fg(Color::Red)).bold(),
+            ]),
+        };
+
+        let list = List::new(items)
+            .highlight_style(Style::default().bg(Color::DarkGray))
+            .block(
+                Block::default()
+                    .borders(Borders::ALL)
+                    .border_type(BorderType::Rounded)
+                    .border_style(Style::default().fg(Color::Cyan))
+                    .title(Span::styled(
+                        " Bookmarks ",
+                        Style::default().fg(Color::Cyan).bold(),
+                    ))
+                    .title_bottom(bottom),
+            );
+
+        ratatui::widgets::StatefulWidget::render(list, area, buf, &mut menu.state);
+    }
+
+    fn render_recent(&mut self, area: Rect, buf: &mut Buffer) {
+        let Some(menu) = &mut self.recent_menu else {
+            return;
+        };
+
+        let items: Vec<ListItem> = self
+            .recent
+            .dirs
+            .iter()
+            .map(|dir| ListItem::new(dir.to_string_lossy().to_string()))
+            .collect();
+
+        let list = List::new(items)
+            .highlight_style(Style::default().fg(Color::Blue).bg(Color::White))
+            .block(
+                Block::default()
+                    .borders(Borders::ALL)
+                    .border_type(BorderType::Rounded)
+                    .border_style(Style::default().fg(Color::Cyan))
+                    .title(Span::styled(
+                        " Recent directories ",
+                        Style::default().fg(Color::Cyan).bold(),
+                    ))
+                    .title_bottom(Line::from(vec![
+                        Span::styled("⏎ Go  ", Style::default().fg(Color::Cyan)).bold(),
+                        Span::styled("Esc Close", Style::default().fg(Color::Red)).bold(),
+                    ])),
+            );
+
+        ratatui::widgets::StatefulWidget::render(list, area, buf, menu);
+    }
+
+    fn render_command_prompt(&mut self, area: Rect, buf: &mut Buffer) {
+        let text = Paragraph::new(self.command_prompt.user_input.to_string()).block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title(vec![Span::from(" Command (%f file, %s selection, %d dir)")])
+                .title_bottom(vec![
+                    Span::styled("⏎ Run  ", Style::default().fg(Color::Cyan)).bold(),
+                    Span::styled("& Background", Style::default().fg(Color::Cyan)).bold(),
+                    Span::raw("  "),
+                    Span::styled("Esc Close", Style::default().fg(Color::Red)).bold(),
+                ]),
+        );
+
+        text.render(area, buf);
+    }
+
+    fn render_log_panel(&mut self, area: Rect, buf: &mut Buffer) {
+        let mut lines: Vec<Line> = Vec::new();
+        for message in self.log.messages() {
+            let mut text = message.text.lines();
+            lines.push(Line::from(vec![
+                Span::styled(
+                    message.time.format("%H:%M:%S ").to_string(),
+                    Style::default().fg(Color::DarkGray),
+                ),
+                Span::styled(
+                    format!("{:5} ", message.level.name()),
+                    level_style(message.level),
+                ),
+                Span::styled(format!("{}: ", message.action), Style::default().bold()),
+                Span::raw(text.next().unwrap_or("").to_string()),
+            ]));
+            // output spanning several lines is indented below the first one
+            lines.extend(text.map(|line| Line::raw(format!("{:15}{}", "", line))));
+        }
+
+        let Some(scroll) = &mut self.log_panel else {
+            return;
+        };
+
+        // `y` counts from the bottom, so new messages stay in view
+        let height = area.height.saturating_sub(2) as usize;
+        let max = lines.len().saturating_sub(height);
+        scroll.y = scroll.y.min(max as u16);
+        let top = max - scroll.y as usize;
+
+        let text = Paragraph::new(lines).scroll((top as u16, 0)).block(
+            Block::default()
+                .borders(Borders::ALL)
+                .border_type(BorderType::Rounded)
+                .border_style(Style::default().fg(Color::Cyan))
+                .title(Span::styled(
+                    " Log ",
+                    Style::default().fg(Color::Cyan).bold(),
+                ))
+                .title_bottom(Line::from(vec![
+                    Span::styled("↑↓ Scroll  ", Style::default().fg(Color::Cyan)).bold(),
+                    Span::styled("Esc Close", Style::default().fg(Color::Red)).bold(),
+                ])),
+        );
+
+        text.render(area, buf);
+    }
+
+    /// What the keyboard is doing right now, for the status bar.
+    fn mode(&self) -> &'static str {
+        if self.notes.create_folder.is_active || self.notes.creat_file.is_active {
+            "CREATE"
+        } else if self
+            .bookmark_menu
+            .as_ref()
+            .is_some_and(|menu| menu.rename.is_some())
+        {
+            "RENAME"
+        } else if self.command_prompt.is_active {
+            "COMMAND"
+        } else if self.goto_prompt.is_some() || self.jump_prompt.is_some() {
+            "GO TO"
+        } else if self.pending_key == Some('Y') {
+            "COPY"
+        } else if self.pending_key.is_some() {
+            "MARK"
+        } else if self.pattern_prompt.is_some() {
+            "SELECT"
+        } else if self.visual.is_some() {
+            "VISUAL"
+        } else if self.picker.is_some() {
+            "PICK"
+        } else {
+            "NORMAL"
+        }
+    }
+
+    /// The bottom line: mode, position, selection, the latest message and on
+    /// the right the listing's settings and free disk space.
+    fn render_status_bar(&self, area: Rect, buf: &mut Buffer) {
+        let mut left = vec![
+            Span::styled(
+                format!(" {} ", self.mode()),
+                Style::default().fg(Color::Black).bg(Color::Cyan).bold(),
+            ),
+            Span::raw(format!(
+                " {}/{} ",
+                self.notes.state.selected().map_or(0, |index| index + 1),
+                self.notes.items.len()
+            )),
+        ];
+
+        if !self.notes.selected_items.is_empty() {
+            let size = self.notes.selection_size();
+            left.push(Span::styled(
+                format!(
+                    " {} selected, {} ",
+                    self.notes.selected_items.len(),
+                    disk::human_size(size)
+                ),
+                Style::default().fg(Color::Yellow),
+            ));
+        }
+
+        if let Some(clipboard) = &self.clipboard {
+            let action = match clipboard.mode {
+                ClipMode::Yank => "yanked",
+                ClipMode::Cut => "cut",
+            };
+            left.push(Span::styled(
+                format!(" {} {} ", clipboard.items.len(), action),
+                Style::default().fg(Color::Magenta),
+            ));
+        }
+
+        // warnings and errors stand out while they flash, older messages are dimmed
+        let message = match self.log.flash() {
+            Some(message) => Some((message, level_style(message.level))),
+            None => self
+                .log
+                .messages()
+                .next_back()
+                .map(|message| (message, Style::default().fg(Color::DarkGray))),
+        };
+        if let Some((message, style)) = message {
+            left.push(Span::styled(
+                format!(
+                    " {}: {}",
+                    message.action,
+                    message.text.lines().next().unwrap_or("")
+                ),
+                style,
+            ));
+        }
+
+        let mut right = format!(" sort: {}", self.notes.sort.name());
+        if self.notes.show_hidden {
+            right.push_str(" +hidden");
+        }
+        if let Some((free, total)) = disk::space(self.last_dir()) {
+            right.push_str(&format!(
+                "  {} free of {} ",
+                disk::human_size(free),
+                disk::human_size(total)
+            ));
+        }
+
+        let layout = Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints([
+                Constraint::Fill(1),
+                Constraint::Length(right.chars().count() as u16),
+            ])
+            .split(area);
+
+        Paragraph::new(Line::from(left)).render(layout[0], buf);
+        Paragraph::new(Span::styled(right, Style::default().fg(Color::DarkGray)))
+            .render(layout[1], buf);
+    }
+
+    fn render_command_output(&mut self, area: Rect, buf: &mut Buffer) {
+        let Some(output) = &self.command_output else {
+            return;
+        };
+
+        let text = Paragraph::new(output.text.as_str())
+            .scroll((output.scroll.y, output.scroll.x))
+            .block(
+                Block::default()
+                    .borders(Borders::ALL)
+                    .border_type(BorderType::Rounded)
+                    .border_style(Style::default().fg(Color::Cyan))
+                    .title(Line::from(vec![
+                        Span::styled(" Output: ", Style::default().fg(Color::Cyan).bold()),
+                        Span::raw(output.command.as_str()),
+                    ]))
+                    .title_bottom(Line::from(vec![
+                        Span::styled("↑↓←→ Scroll  ", Style::default().fg(Color::Cyan)).bold(),
+                        Span::styled("Esc Close", Style::default().fg(Color::Red)).bold(),
+                    ])),
+            );
+
+        text.render(area, buf);
+    }
+
+    fn render_pattern_prompt(&self, area: Rect, buf: &mut Buffer) {
+        let Some(prompt) = &self.pattern_prompt else {
+            return;
+        };
+
+        let action = if prompt.select { "Select" } else { "Deselect" };
+        // show how many entries the pattern matches while typing it
+        let matches = match NamePattern::parse(&prompt.user_input) {
+            Ok(pattern) => format!(" ({} matching)", self.notes.matching(&pattern).len()),
+            Err(_) => String::new(),
+        };
+
+        let text = Paragraph::new(prompt.user_input.to_string()).block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title(vec![Span::from(format!(
+                    " {} by glob or /regex/{}",
+                    action, matches
+                ))])
+                .title_bottom(vec![
+                    Span::styled(format!("⏎ {}", action), Style::default().fg(Color::Cyan)).bold(),
+                    Span::raw("  "),
+                    Span::styled("Esc Close", Style::default().fg(Color::Red)).bold(),
+                ]),
+        );
+
+        text.render(area, buf);
+    }
+
     fn render_input_button(&mut self, area: Rect, buf: &mut Buffer) {
         if self.notes.create_folder.is_active {
-            let text = Paragraph::new(format!("{}", self.notes.create_folder.user_input)).block(
+            let text = Paragraph::new(self.notes.create_folder.user_input.to_string()).block(
                 Block::default()
                     .borders(Borders::ALL)
                     .title(vec![Span::from(" Input Folder Name")])
@@ -1022,7 +3589,7 @@ impl App {
 
             text.render(area, buf);
         } else {
-            let text = Paragraph::new(format!("{}", self.notes.creat_file.user_input)).block(
+            let text = Paragraph::new(self.notes.creat_file.user_input.to_string()).block(
                 Block::default()
                     .borders(Borders::ALL)
                     .title(vec![Span::from(" Input File Name")])
@@ -1038,8 +3605,40 @@ impl App {
     }
 }
 
+/// Moves a menu's cursor up, from the first entry to the last.
+fn cursor_up(state: &mut ListState, len: usize) {
+    let i = match state.selected() {
+        Some(0) | None => len.saturating_sub(1),
+        Some(i) => i - 1,
+    };
+    state.select(Some(i));
+}
+
+/// Moves a menu's cursor down, from the last entry to the first.
+fn cursor_down(state: &mut ListState, len: usize) {
+    let i = match state.selected() {
+        Some(i) if i + 1 < len => i + 1,
+        _ => 0,
+    };
+    state.select(Some(i));
+}
+
+fn level_style(level: Level) -> Style {
+    match level {
+        Level::Info => Style::default().fg(Color::Green),
+        Level::Warning => Style::default().fg(Color::Yellow),
+        Level::Error => Style::default().fg(Color::Red).bold(),
+    }
+}
+
 impl Widget for &mut App {
     fn render(self, area: Rect, buf: &mut Buffer) {
+        let status_layout = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([Constraint::Fill(1), Constraint::Length(1)])
+            .split(area);
+        let area = status_layout[0];
+
         let layout = Layout::default()
             .direction(Direction::Horizontal)
             .constraints([Constraint::Percentage(60), Constraint::Percentage(40)])
@@ -1082,15 +3681,66 @@ impl Widget for &mut App {
             ])
             .split(input_button_area[1]);
 
+        let open_with_area = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([
+                Constraint::Percentage(30),
+                Constraint::Fill(1),
+                Constraint::Percentage(30),
+            ])
+            .split(input_button_area[1]);
+
         if self.help {
             self.render_help(overlay[1], buf);
+        } else if self.command_prompt.is_active {
+            self.render_command_prompt(input_button[1], buf);
+        } else if self.command_output.is_some() {
+            self.render_command_output(overlay[1], buf);
+        } else if self.open_with.is_some() {
+            self.render_open_with(open_with_area[1], buf);
+        } else if self.recent_menu.is_some() {
+            self.render_recent(overlay[1], buf);
+        } else if self.bookmark_menu.is_some() {
+            self.render_bookmarks(overlay[1], buf);
+        } else if self.jump_prompt.is_some() {
+            self.render_jump_prompt(overlay[1], buf);
+        } else if self.goto_prompt.is_some() {
+            self.render_goto_prompt(overlay[1], buf);
+        } else if self.pattern_prompt.is_some() {
+            self.render_pattern_prompt(input_button[1], buf);
+        } else if self.log_panel.is_some() {
+            self.render_log_panel(overlay[1], buf);
         } else if self.notes.create_folder.is_active || self.notes.creat_file.is_active {
             self.render_input_button(input_button[1], buf);
+        } else if self.other_pane.is_some() {
+            self.render_dual_pane(area, buf);
+        } else if self.miller {
+            self.render_miller(area, buf);
         } else {
-            self.render_list(second_sub_layout[0], buf);
+            // the tab bar only shows up once there is more than one tab
+            let tab_layout = Layout::default()
+                .direction(Direction::Vertical)
+                .constraints([
+                    Constraint::Length(if self.tab_count() > 1 { 1 } else { 0 }),
+                    Constraint::Fill(1),
+                ])
+                .split(second_sub_layout[0]);
+
+            self.areas.tabs = tab_layout[0];
+            self.areas.list = tab_layout[1];
+            self.areas.other_pane = Rect::default();
+            self.areas.info = sub_layout[1];
+            self.areas.preview = sub_layout[0];
+            self.areas.selection = second_sub_layout[1];
+
+            self.render_tabs(tab_layout[0], buf);
+            self.render_list(tab_layout[1], buf);
             self.render_file_info(sub_layout[1], buf);
             self.render_file_preview(sub_layout[0], buf);
             self.render_selection(second_sub_layout[1], buf);
         }
+
+        // drawn last so it sees the cursor the listing has settled on
+        self.render_status_bar(status_layout[1], buf);
     }
 }
@@ -0,0 +1,350 @@
+use glob::Pattern;
+use std::os::unix::fs::PermissionsExt;
+use std::path::Path;
+use std::process::Command;
+use std::{fmt, fs};
+
+/// How an opener's command is started.
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub enum LaunchMode {
+    /// GUI programs, started in the background (the default).
+    #[default]
+    Detached,
+    /// Terminal programs like vim, the UI is suspended while they run (`flag t`).
+    Terminal,
+}
+
+/// Something an opener rule checks before it applies to a file.
+enum Condition {
+    /// `ext pdf|djvu`
+    Ext(Vec<String>),
+    /// `mime image/*`, as reported by `file --mime-type`
+    Mime(Pattern),
+    /// `glob *.tar.gz`, matched against the file name
+    Glob(Pattern),
+    /// `executable`
+    Executable,
+    /// `dir`
+    Dir,
+}
+
+/// One rule of the `[openers]` config section, modelled after ranger's rifle.
+///
+/// ```text
+/// ext pdf|djvu = zathura {path}
+/// mime text/*, flag t = vim {path}
+/// glob *.tar.gz = tar xzf {path} -C {dir}
+/// executable, flag t = {path}
+/// = xdg-open {path}
+/// ```
+///
+/// All conditions before the `=` must match, a rule without conditions
+/// matches everything. In the command `{path}` is the file, `{dir}` the
+/// directory being browsed and `{selection}` all selected files.
+pub struct Opener {
+    conditions: Vec<Condition>,
+    pub command: String,
+    pub mode: LaunchMode,
+}
+
+impl fmt::Display for Opener {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.mode {
+            LaunchMode::Detached => write!(f, "{}", self.command),
+            LaunchMode::Terminal => write!(f, "{} (terminal)", self.command),
+        }
+    }
+}
+
+impl Opener {
+    pub fn parse(line: &str) -> Result<Opener, String> {
+        let Some((conditions, command)) = line.split_once('=') else {
+            return Err("expected `conditions = command`".to_string());
+        };
+
+        let command = command.trim().to_string();
+        if command.is_empty() {
+            return Err("missing command after `=`".to_string());
+        }
+
+        let mut opener = Opener {
+            conditions: Vec::new(),
+            command,
+            mode: LaunchMode::default(),
+        };
+
+        for condition in conditions.split(',') {
+            let condition = condition.trim();
+            if condition.is_empty() {
+                continue;
+            }
+
+            let (name, value) = condition.split_once(' ').unwrap_or((condition, ""));
+            let value = value.trim();
+            let pattern = |value: &str| {
+                Pattern::new(value).map_err(|err| format!("bad pattern `{}`: {}", value, err))
+            };
+
+            match name {
+                "ext" => opener.conditions.push(Condition::Ext(
+                    value
+                        .split('|')
+                        .map(|ext| ext.trim().trim_start_matches('.').to_lowercase())
+                        .collect(),
+                )),
+                "mime" => opener.conditions.push(Condition::Mime(pattern(value)?)),
+                "glob" => opener.conditions.push(Condition::Glob(pattern(value)?)),
+                "executable" => opener.conditions.push(Condition::Executable),
+                "dir" => opener.conditions.push(Condition::Dir),
+                "flag" => {
+                    for flag in value.chars() {
+                        opener.mode = match flag {
+                            't' => LaunchMode::Terminal,
+                            'd' => LaunchMode::Detached,
+                            _ => return Err(format!("unknown flag `{}`", flag)),
+                        };
+                    }
+                }
+                _ => return Err(format!("unknown condition `{}`", name)),
+            }
+        }
+
+        Ok(opener)
+    }
+
+    pub fn matches(&self, path: &Path) -> bool {
+        self.conditions.iter().all(|condition| match condition {
+            Condition::Ext(extensions) => path
+                .extension()
+                .map(|ext| extensions.contains(&ext.to_string_lossy().to_lowercase()))
+                .unwrap_or(false),
+            Condition::Mime(pattern) => pattern.matches(&mime_type(path)),
+            Condition::Glob(pattern) => path
+                .file_name()
+                .map(|name| pattern.matches(&name.to_string_lossy()))
+                .unwrap_or(false),
+            Condition::Executable => {
+                path.is_file()
+                    && fs::metadata(path)
+                        .map(|metadata| metadata.permissions().mode() & 0o111 != 0)
+                        .unwrap_or(false)
+            }
+            Condition::Dir => path.is_dir(),
+        })
+    }
+
+    /// The shell command with the placeholders filled in and quoted.
+    pub fn command_for(&self, path: &str, dir: &Path, selection: &[String]) -> String {
+        // one pass over the command, so placeholders inside the file names
+        // that were filled in are left alone
+        let mut expanded = String::new();
+        let mut rest = self.command.as_str();
+
+        while let Some(start) = rest.find('{') {
+            expanded.push_str(&rest[..start]);
+            rest = &rest[start..];
+
+            if let Some(after) = rest.strip_prefix("{path}") {
+                expanded.push_str(&shell_quote(path));
+                rest = after;
+            } else if let Some(after) = rest.strip_prefix("{dir}") {
+                expanded.push_str(&shell_quote(&dir.to_string_lossy()));
+                rest = after;
+            } else if let Some(after) = rest.strip_prefix("{selection}") {
+                expanded.push_str(&quote_selection(path, selection));
+                rest = after;
+            } else {
+                expanded.push('{');
+                rest = &rest[1..];
+            }
+        }
+
+        expanded.push_str(rest);
+        expanded
+    }
+}
+
+/// What cb-ls does when the config has no `[openers]` section.
+pub fn default_openers() -> Vec<Opener> {
+    vec![Opener {
+        conditions: Vec::new(),
+        command: String::from("xdg-open {path}"),
+        mode: LaunchMode::Detached,
+    }]
+}
+
+/// Fills in the placeholders of a command typed at the `:` prompt:
+/// `%f` the file under the cursor, `%s` the selection (or `%f` if nothing is
+/// selected), `%d` the current directory and `%%` a literal `%`.
+pub fn expand_placeholders(command: &str, file: &str, dir: &Path, selection: &[String]) -> String {
+    let mut expanded = String::new();
+    let mut chars = command.chars();
+
+    while let Some(c) = chars.next() {
+        if c != '%' {
+            expanded.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some('f') => expanded.push_str(&shell_quote(file)),
+            Some('d') => expanded.push_str(&shell_quote(&dir.to_string_lossy())),
+            Some('s') => expanded.push_str(&quote_selection(file, selection)),
+            Some('%') => expanded.push('%'),
+            Some(other) => {
+                expanded.push('%');
+                expanded.push(other);
+            }
+            None => expanded.push('%'),
+        }
+    }
+
+    expanded
+}
+
+/// The selection quoted and separated by spaces, or `file` when nothing is
+/// selected.
+fn quote_selection(file: &str, selection: &[String]) -> String {
+    if selection.is_empty() {
+        return shell_quote(file);
+    }
+    selection
+        .iter()
+        .map(|item| shell_quote(item))
+        .collect::<Vec<String>>()
+        .join(" ")
+}
+
+/// Wraps `text` in single quotes so `sh` takes it literally.
+pub fn shell_quote(text: &str) -> String {
+    format!("'{}'", text.replace('\'', r"'\''"))
+}
+
+fn mime_type(path: &Path) -> String {
+    match Command::new("file")
+        .arg("--mime-type")
+        .arg("--brief")
+        .arg("--")
+        .arg(path)
+        .output()
+    {
+        Ok(output) if output.status.success() => {
+            String::from_utf8_lossy(&output.stdout).trim().to_string()
+        }
+        _ => String::from("application/octet-stream"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn opener(command: &str) -> Opener {
+        Opener::parse(&format!("= {}", command)).unwrap()
+    }
+
+    /// What `sh` makes of `command`: one line per argument of `printf`.
+    fn sh_args(command: &str) -> Vec<String> {
+        let output = Command::new("sh")
+            .arg("-c")
+            .arg(format!("printf '%s\\n' {}", command))
+            .output()
+            .unwrap();
+        String::from_utf8(output.stdout)
+            .unwrap()
+            .lines()
+            .map(String::from)
+            .collect()
+    }
+
+    #[test]
+    fn fills_in_placeholders() {
+        let command = opener("tar xzf {path} -C {dir}").command_for(
+            "/tmp/a b.tar.gz",
+            Path::new("/tmp"),
+            &[],
+        );
+        assert_eq!(command, "tar xzf '/tmp/a b.tar.gz' -C '/tmp'");
+    }
+
+    #[test]
+    fn selection_falls_back_to_path() {
+        let command = opener("cat {selection}").command_for("/tmp/a", Path::new("/tmp"), &[]);
+        assert_eq!(command, "cat '/tmp/a'");
+    }
+
+    #[test]
+    fn placeholder_in_file_name_is_not_expanded() {
+        let selection = vec![String::from("/tmp/x/a;echo PWNED;'")];
+        let command =
+            opener("cat {path}").command_for("/tmp/x/{selection}", Path::new("/tmp/x"), &selection);
+        assert_eq!(command, "cat '/tmp/x/{selection}'");
+        assert_eq!(sh_args(&command["cat ".len()..]), ["/tmp/x/{selection}"]);
+    }
+
+    #[test]
+    fn placeholders_in_every_value_stay_literal() {
+        let selection = vec![String::from("/d/{path}"), String::from("/d/it's {dir}")];
+        let command = opener("{path} {dir} {selection}").command_for(
+            "/d/{dir}",
+            Path::new("/{selection}"),
+            &selection,
+        );
+        assert_eq!(
+            sh_args(&command),
+            ["/d/{dir}", "/{selection}", "/d/{path}", "/d/it's {dir}"]
+        );
+    }
+
+    #[test]
+    fn unknown_braces_are_kept() {
+        let command = opener("echo {} {nope} {path").command_for("/a", Path::new("/"), &[]);
+        assert_eq!(command, "echo {} {nope} {path");
+    }
+
+    #[test]
+    fn quotes_for_the_shell() {
+        assert_eq!(shell_quote("plain"), "'plain'");
+        assert_eq!(shell_quote(""), "''");
+        assert_eq!(shell_quote("it's"), r"'it'\''s'");
+        for text in [
+            "a b",
+            "it's",
+            "$(rm -rf ~)",
+            "`id`",
+            "a;b|c&d",
+            "'''",
+            "\\n",
+        ] {
+            assert_eq!(sh_args(&shell_quote(text)), [text]);
+        }
+    }
+
+    #[test]
+    fn expands_prompt_placeholders() {
+        let selection = vec![String::from("/d/a b"), String::from("/d/c")];
+        let command = expand_placeholders("ls %f %d %s 100%%", "/d/x", Path::new("/d"), &selection);
+        assert_eq!(command, "ls '/d/x' '/d' '/d/a b' '/d/c' 100%");
+    }
+
+    #[test]
+    fn prompt_selection_falls_back_to_file() {
+        let command = expand_placeholders("wc %s", "/d/x", Path::new("/d"), &[]);
+        assert_eq!(command, "wc '/d/x'");
+    }
+
+    #[test]
+    fn unknown_and_trailing_percent_are_kept() {
+        let command = expand_placeholders("printf %q 50%", "/d/x", Path::new("/d"), &[]);
+        assert_eq!(command, "printf %q 50%");
+    }
+
+    #[test]
+    fn prompt_placeholders_in_file_names_stay_literal() {
+        let selection = vec![String::from("/d/%f;echo PWNED;'")];
+        let command = expand_placeholders("echo %s %f", "/d/%s", Path::new("/d"), &selection);
+        assert_eq!(
+            sh_args(&command["echo ".len()..]),
+            ["/d/%f;echo PWNED;'", "/d/%s"]
+        );
+    }
+}
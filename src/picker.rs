@@ -0,0 +1,64 @@
+use std::fs;
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+
+/// State for `--pick`, where cb-ls hands paths back instead of opening them.
+#[derive(Default)]
+pub struct Picker {
+    /// Only directories can be picked.
+    pub dirs_only: bool,
+    /// Only files with one of these extensions can be picked, any if empty.
+    pub extensions: Vec<String>,
+    /// Where to write the picked paths, stdout if unset.
+    pub output: Option<PathBuf>,
+    /// Separate paths with NUL instead of newlines.
+    pub null_separated: bool,
+    /// What the user confirmed, `None` until then (or if they cancelled).
+    pub picked: Option<Vec<String>>,
+}
+
+impl Picker {
+    pub fn accepts(&self, item: &str) -> bool {
+        let path = Path::new(item);
+
+        if self.dirs_only {
+            return path.is_dir();
+        }
+        if path.is_dir() {
+            return false;
+        }
+        if self.extensions.is_empty() {
+            return true;
+        }
+
+        match path.extension() {
+            Some(ext) => {
+                let ext = ext.to_string_lossy().to_lowercase();
+                self.extensions.contains(&ext)
+            }
+            None => false,
+        }
+    }
+
+    pub fn write(&self) -> io::Result<()> {
+        let Some(picked) = &self.picked else {
+            return Ok(());
+        };
+
+        let separator = if self.null_separated { '\0' } else { '\n' };
+        let mut text = String::new();
+        for path in picked {
+            text.push_str(path);
+            text.push(separator);
+        }
+
+        match &self.output {
+            Some(file) => fs::write(file, text),
+            None => {
+                let mut stdout = io::stdout();
+                stdout.write_all(text.as_bytes())?;
+                stdout.flush()
+            }
+        }
+    }
+}
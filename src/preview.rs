@@ -0,0 +1,164 @@
+use crate::events::AppEvent;
+use crate::sort::SortMode;
+use std::fs::{self, File, FileType};
+use std::io::Read;
+use std::os::unix::fs::FileTypeExt;
+use std::path::Path;
+use std::sync::mpsc::Sender;
+use std::thread;
+
+/// Files are only read up to this size for the preview.
+const MAX_PREVIEW_BYTES: u64 = 256 * 1024;
+
+pub enum PreviewContent {
+    Text(String),
+    /// Entries of a directory, like `ls` would print them.
+    Dir(Vec<String>),
+}
+
+/// The preview pane's content, loaded off the main thread.
+#[derive(Default)]
+pub struct Preview {
+    /// The entry being previewed (or loaded).
+    path: Option<String>,
+    /// Hidden files and sort order directories are listed with, those of
+    /// the listing the preview is for.
+    listing: (bool, SortMode),
+    /// `None` while loading.
+    pub content: Option<PreviewContent>,
+}
+
+impl Preview {
+    /// Starts loading `path` unless it is already shown or on its way.
+    /// Directories are listed with `show_hidden` and `sort`.
+    pub fn request(
+        &mut self,
+        path: &str,
+        show_hidden: bool,
+        sort: SortMode,
+        sender: &Sender<AppEvent>,
+    ) {
+        if self.path.as_deref() == Some(path) && self.listing == (show_hidden, sort) {
+            return;
+        }
+
+        self.path = Some(path.to_string());
+        self.listing = (show_hidden, sort);
+        self.content = None;
+
+        let sender = sender.clone();
+        let path = path.to_string();
+        thread::spawn(move || {
+            let content = load(Path::new(&path), show_hidden, sort);
+            let _ = sender.send(AppEvent::PreviewReady { path, content });
+        });
+    }
+
+    /// Makes the next [`Preview::request`] load again, e.g. after the file changed.
+    pub fn invalidate(&mut self) {
+        self.path = None;
+    }
+
+    /// Takes a loaded preview, unless the cursor has moved on since it was requested.
+    pub fn receive(&mut self, path: String, content: PreviewContent) -> bool {
+        if self.path.as_ref() != Some(&path) {
+            return false;
+        }
+        self.content = Some(content);
+        true
+    }
+}
+
+fn load(path: &Path, show_hidden: bool, sort: SortMode) -> PreviewContent {
+    if path.is_dir() {
+        return PreviewContent::Dir(list_dir(path, show_hidden, sort));
+    }
+
+    // links are followed, a link to a FIFO must not be opened either
+    let file_type = match fs::metadata(path) {
+        Ok(metadata) => metadata.file_type(),
+        Err(err) => return PreviewContent::Text(format!("{}: {}", path.display(), err)),
+    };
+    if let Some(kind) = special_kind(file_type) {
+        return PreviewContent::Text(format!("{}: {}, not previewed", path.display(), kind));
+    }
+
+    let mut bytes = Vec::new();
+    if let Ok(file) = File::open(path) {
+        let _ = file.take(MAX_PREVIEW_BYTES).read_to_end(&mut bytes);
+    }
+
+    match String::from_utf8(bytes) {
+        Ok(text) => PreviewContent::Text(text),
+        // the limit may have cut a character in half
+        Err(err) if err.utf8_error().error_len().is_none() => {
+            let valid = err.utf8_error().valid_up_to();
+            let mut bytes = err.into_bytes();
+            bytes.truncate(valid);
+            PreviewContent::Text(String::from_utf8(bytes).unwrap_or_default())
+        }
+        // binary files just show their path
+        Err(_) => PreviewContent::Text(path.to_string_lossy().to_string()),
+    }
+}
+
+/// The text of the file at `path`, for copying it. Only regular text files
+/// the preview would read in full are accepted.
+pub fn read_text(path: &Path) -> Result<String, String> {
+    let error = |text: &str| format!("{}: {}", path.display(), text);
+
+    let metadata = fs::metadata(path).map_err(|err| error(&err.to_string()))?;
+    if metadata.is_dir() {
+        return Err(error("Is a directory"));
+    }
+    if let Some(kind) = special_kind(metadata.file_type()) {
+        return Err(error(&format!("{}, not read", kind)));
+    }
+    if metadata.len() > MAX_PREVIEW_BYTES {
+        return Err(error(&format!(
+            "Bigger than {} KiB",
+            MAX_PREVIEW_BYTES / 1024
+        )));
+    }
+
+    let mut bytes = Vec::new();
+    File::open(path)
+        .and_then(|file| file.take(MAX_PREVIEW_BYTES).read_to_end(&mut bytes))
+        .map_err(|err| error(&err.to_string()))?;
+    String::from_utf8(bytes).map_err(|_| error("Not a text file"))
+}
+
+/// What `file_type` is when it isn't a regular file or directory. Opening a
+/// FIFO blocks until someone writes to it and devices can be endless, so
+/// only regular files are ever read.
+fn special_kind(file_type: FileType) -> Option<&'static str> {
+    if file_type.is_file() || file_type.is_dir() {
+        None
+    } else if file_type.is_fifo() {
+        Some("named pipe")
+    } else if file_type.is_socket() {
+        Some("socket")
+    } else if file_type.is_block_device() {
+        Some("block device")
+    } else if file_type.is_char_device() {
+        Some("character device")
+    } else {
+        Some("special file")
+    }
+}
+
+/// Entries of `path` as a listing with these settings shows them.
+pub fn list_dir(path: &Path, show_hidden: bool, sort: SortMode) -> Vec<String> {
+    let Ok(entries) = fs::read_dir(path) else {
+        return Vec::new();
+    };
+
+    let mut items: Vec<String> = entries
+        .filter_map(Result::ok)
+        .filter(|entry| show_hidden || !entry.file_name().to_string_lossy().starts_with('.'))
+        .map(|entry| entry.path().to_string_lossy().to_string())
+        .collect();
+
+    sort.sort(&mut items);
+    items
+}
@@ -0,0 +1,56 @@
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+/// How many directories the list keeps.
+const MAX_RECENT: usize = 100;
+
+/// Directories visited lately, most recent first.
+///
+/// Kept between sessions in `$XDG_DATA_HOME/cb-ls/recent`, one path per line.
+#[derive(Default)]
+pub struct Recent {
+    pub dirs: Vec<PathBuf>,
+}
+
+impl Recent {
+    pub fn default_path() -> Option<PathBuf> {
+        Some(crate::config::data_dir()?.join("recent"))
+    }
+
+    /// Reads the list, a missing or unreadable file is just an empty list.
+    pub fn load(path: &Path) -> Recent {
+        let dirs = fs::read_to_string(path)
+            .map(|content| {
+                content
+                    .lines()
+                    .filter(|line| !line.is_empty())
+                    .map(PathBuf::from)
+                    .take(MAX_RECENT)
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        Recent { dirs }
+    }
+
+    /// Moves `dir` to the top of the list.
+    pub fn visit(&mut self, dir: &Path) {
+        if self.dirs.first().map(PathBuf::as_path) == Some(dir) {
+            return;
+        }
+
+        self.dirs.retain(|recent| recent != dir);
+        self.dirs.insert(0, dir.to_path_buf());
+        self.dirs.truncate(MAX_RECENT);
+    }
+
+    pub fn save(&self, path: &Path) -> io::Result<()> {
+        crate::config::write_data_file(
+            path,
+            self.dirs
+                .iter()
+                .map(|dir| dir.to_string_lossy().to_string()),
+        )
+    }
+}
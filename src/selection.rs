@@ -0,0 +1,95 @@
+use glob::Pattern;
+use regex::Regex;
+use std::collections::BTreeMap;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+/// What `+` and `-` match file names against: a regex when the input is
+/// wrapped in slashes (`/\.log$/`), a glob (`*.log`) otherwise.
+pub enum NamePattern {
+    Glob(Pattern),
+    Regex(Regex),
+}
+
+impl NamePattern {
+    pub fn parse(input: &str) -> Result<NamePattern, String> {
+        let input = input.trim();
+        if input.is_empty() {
+            return Err("empty pattern".to_string());
+        }
+
+        match input
+            .strip_prefix('/')
+            .and_then(|input| input.strip_suffix('/'))
+        {
+            Some(regex) => Regex::new(regex)
+                .map(NamePattern::Regex)
+                .map_err(|err| err.to_string()),
+            None => Pattern::new(input)
+                .map(NamePattern::Glob)
+                .map_err(|err| format!("bad glob `{}`: {}", input, err)),
+        }
+    }
+
+    pub fn matches(&self, name: &str) -> bool {
+        match self {
+            NamePattern::Glob(pattern) => pattern.matches(name),
+            NamePattern::Regex(regex) => regex.is_match(name),
+        }
+    }
+}
+
+/// Visual mode, `v`: the entries between `anchor` and the cursor are
+/// selected on top of what was selected before.
+pub struct VisualRange {
+    /// The listing the range is in; leaving it ends visual mode.
+    pub dir: PathBuf,
+    pub anchor: usize,
+    /// The selection when visual mode started, restored by Esc.
+    pub before: Vec<String>,
+    /// The selection as visual mode last set it.
+    pub applied: Vec<String>,
+}
+
+/// The selected paths grouped by their parent directory, directories and
+/// the entries in them in natural order. This is the order of the
+/// selection panel.
+pub fn grouped(items: &[String]) -> Vec<(PathBuf, Vec<&String>)> {
+    let mut groups: BTreeMap<PathBuf, Vec<&String>> = BTreeMap::new();
+    for item in items {
+        let parent = Path::new(item).parent().unwrap_or(Path::new("/"));
+        groups.entry(parent.to_path_buf()).or_default().push(item);
+    }
+
+    groups
+        .into_iter()
+        .map(|(parent, mut items)| {
+            items.sort_by(|a, b| natord::compare(a, b));
+            (parent, items)
+        })
+        .collect()
+}
+
+/// `$XDG_DATA_HOME/cb-ls/selection`, where `keep_selection = true` keeps the
+/// selection between sessions, one path per line.
+pub fn default_path() -> Option<PathBuf> {
+    Some(crate::config::data_dir()?.join("selection"))
+}
+
+/// Reads a saved selection, leaving out paths that are gone by now.
+pub fn load(path: &Path) -> Vec<String> {
+    fs::read_to_string(path)
+        .map(|content| {
+            content
+                .lines()
+                .filter(|line| !line.is_empty() && fs::symlink_metadata(line).is_ok())
+                .map(String::from)
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+pub fn save(path: &Path, items: &[String]) -> io::Result<()> {
+    crate::config::write_data_file(path, items.iter().cloned())
+}
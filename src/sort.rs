@@ -0,0 +1,78 @@
+use natord::compare;
+use std::fs;
+use std::path::Path;
+use std::time::SystemTime;
+
+/// Order in which `FileList` shows its entries.
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub enum SortMode {
+    /// Natural order by name, so `file2` comes before `file10`.
+    #[default]
+    Name,
+    Size,
+    Modified,
+    Extension,
+    /// Whatever order the filesystem hands out.
+    None,
+}
+
+impl SortMode {
+    pub const NAMES: &'static str = "name, size, modified, extension, none";
+
+    pub fn from_name(name: &str) -> Option<SortMode> {
+        match name.trim().to_lowercase().as_str() {
+            "name" => Some(SortMode::Name),
+            "size" => Some(SortMode::Size),
+            "modified" | "mtime" | "time" => Some(SortMode::Modified),
+            "extension" | "ext" => Some(SortMode::Extension),
+            "none" => Some(SortMode::None),
+            _ => None,
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            SortMode::Name => "name",
+            SortMode::Size => "size",
+            SortMode::Modified => "modified",
+            SortMode::Extension => "extension",
+            SortMode::None => "none",
+        }
+    }
+
+    pub fn sort(&self, items: &mut [String]) {
+        match self {
+            SortMode::Name => items.sort_by(|a, b| compare(a, b)),
+            // biggest and newest first, like `ls -S` and `ls -t`
+            SortMode::Size => {
+                items.sort_by(|a, b| size(b).cmp(&size(a)).then_with(|| compare(a, b)))
+            }
+            SortMode::Modified => {
+                items.sort_by(|a, b| modified(b).cmp(&modified(a)).then_with(|| compare(a, b)))
+            }
+            SortMode::Extension => {
+                items.sort_by(|a, b| extension(a).cmp(&extension(b)).then_with(|| compare(a, b)))
+            }
+            SortMode::None => {}
+        }
+    }
+}
+
+fn size(item: &str) -> u64 {
+    fs::metadata(item)
+        .map(|metadata| metadata.len())
+        .unwrap_or(0)
+}
+
+fn modified(item: &str) -> SystemTime {
+    fs::metadata(item)
+        .and_then(|metadata| metadata.modified())
+        .unwrap_or(SystemTime::UNIX_EPOCH)
+}
+
+fn extension(item: &str) -> String {
+    Path::new(item)
+        .extension()
+        .map(|ext| ext.to_string_lossy().to_lowercase())
+        .unwrap_or_default()
+}
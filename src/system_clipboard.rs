@@ -0,0 +1,176 @@
+use crate::tui::TtyWriter;
+use std::io::{self, Write};
+use std::process::{Command, Stdio};
+
+/// How text gets onto the system clipboard.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum CopyMethod {
+    /// OSC 52 when the terminal is known to handle it, else the first
+    /// clipboard tool found, else OSC 52 anyway.
+    Auto,
+    /// The OSC 52 escape sequence, handled by the terminal itself. Works
+    /// over ssh and inside tmux (with `set-clipboard on`).
+    Osc52,
+    WlCopy,
+    Xclip,
+    Xsel,
+}
+
+impl Default for CopyMethod {
+    fn default() -> Self {
+        match std::env::var("CBLS_CLIPBOARD") {
+            Ok(value) => CopyMethod::from_name(&value).unwrap_or(CopyMethod::Auto),
+            Err(_) => CopyMethod::Auto,
+        }
+    }
+}
+
+impl CopyMethod {
+    pub const NAMES: &str = "auto, osc52, wl-copy, xclip, xsel";
+
+    pub fn from_name(name: &str) -> Option<CopyMethod> {
+        match name.trim().to_lowercase().as_str() {
+            "auto" => Some(CopyMethod::Auto),
+            "osc52" => Some(CopyMethod::Osc52),
+            "wl-copy" => Some(CopyMethod::WlCopy),
+            "xclip" => Some(CopyMethod::Xclip),
+            "xsel" => Some(CopyMethod::Xsel),
+            _ => None,
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            CopyMethod::Auto => "auto",
+            CopyMethod::Osc52 => "osc52",
+            CopyMethod::WlCopy => "wl-copy",
+            CopyMethod::Xclip => "xclip",
+            CopyMethod::Xsel => "xsel",
+        }
+    }
+
+    /// Resolves `Auto` to the method that will actually be used.
+    pub fn detect(self) -> CopyMethod {
+        if self != CopyMethod::Auto {
+            return self;
+        }
+        if terminal_has_osc52() {
+            return CopyMethod::Osc52;
+        }
+
+        let wayland = std::env::var_os("WAYLAND_DISPLAY").is_some();
+        let x11 = std::env::var_os("DISPLAY").is_some();
+        if wayland && in_path("wl-copy") {
+            CopyMethod::WlCopy
+        } else if x11 && in_path("xclip") {
+            CopyMethod::Xclip
+        } else if x11 && in_path("xsel") {
+            CopyMethod::Xsel
+        } else {
+            CopyMethod::Osc52
+        }
+    }
+}
+
+/// Puts `text` on the clipboard, returning the method that was used.
+pub fn copy(text: &str, method: CopyMethod) -> io::Result<CopyMethod> {
+    let method = method.detect();
+    let (program, args): (&str, &[&str]) = match method {
+        CopyMethod::Auto | CopyMethod::Osc52 => {
+            let mut tty = TtyWriter::open()?;
+            write!(tty, "\x1b]52;c;{}\x07", base64(text.as_bytes()))?;
+            tty.flush()?;
+            return Ok(method);
+        }
+        CopyMethod::WlCopy => ("wl-copy", &[]),
+        CopyMethod::Xclip => ("xclip", &["-selection", "clipboard"]),
+        CopyMethod::Xsel => ("xsel", &["--clipboard", "--input"]),
+    };
+
+    // the tools fork to keep serving the selection, so their output must
+    // not end up on the screen
+    let mut child = Command::new(program)
+        .args(args)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn()?;
+    if let Some(mut stdin) = child.stdin.take() {
+        stdin.write_all(text.as_bytes())?;
+    }
+    let status = child.wait()?;
+    if !status.success() {
+        return Err(io::Error::other(format!("{} failed: {}", program, status)));
+    }
+    Ok(method)
+}
+
+/// Terminals that are known to accept OSC 52 writes. Over ssh the local
+/// tools would fill the remote clipboard, so the terminal is the only
+/// useful target there.
+fn terminal_has_osc52() -> bool {
+    const TERMS: [&str; 6] = ["kitty", "alacritty", "foot", "wezterm", "ghostty", "tmux"];
+    const PROGRAMS: [&str; 4] = ["iTerm.app", "WezTerm", "ghostty", "vscode"];
+
+    let term = std::env::var("TERM").unwrap_or_default();
+    let program = std::env::var("TERM_PROGRAM").unwrap_or_default();
+
+    std::env::var_os("SSH_TTY").is_some()
+        || std::env::var_os("TMUX").is_some()
+        || TERMS.iter().any(|name| term.contains(name))
+        || PROGRAMS.contains(&program.as_str())
+}
+
+fn in_path(program: &str) -> bool {
+    std::env::var_os("PATH")
+        .is_some_and(|paths| std::env::split_paths(&paths).any(|dir| dir.join(program).is_file()))
+}
+
+fn base64(bytes: &[u8]) -> String {
+    const ALPHABET: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+    let mut encoded = String::with_capacity(bytes.len().div_ceil(3) * 4);
+    for chunk in bytes.chunks(3) {
+        let b = [
+            chunk[0],
+            chunk.get(1).copied().unwrap_or(0),
+            chunk.get(2).copied().unwrap_or(0),
+        ];
+        let n = (b[0] as u32) << 16 | (b[1] as u32) << 8 | b[2] as u32;
+        for i in 0..4 {
+            if i <= chunk.len() {
+                encoded.push(ALPHABET[(n >> (18 - 6 * i) & 63) as usize] as char);
+            } else {
+                encoded.push('=');
+            }
+        }
+    }
+    encoded
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn base64_matches_rfc_4648() {
+        let vectors = [
+            ("", ""),
+            ("f", "Zg=="),
+            ("fo", "Zm8="),
+            ("foo", "Zm9v"),
+            ("foob", "Zm9vYg=="),
+            ("fooba", "Zm9vYmE="),
+            ("foobar", "Zm9vYmFy"),
+        ];
+        for (text, encoded) in vectors {
+            assert_eq!(base64(text.as_bytes()), encoded, "{:?}", text);
+        }
+    }
+
+    #[test]
+    fn base64_uses_the_whole_alphabet() {
+        assert_eq!(base64(&[0xfb, 0xff, 0xbf]), "+/+/");
+        assert_eq!(base64(&[0, 0, 0]), "AAAA");
+    }
+}
@@ -0,0 +1,87 @@
+use crossterm::event::{DisableMouseCapture, EnableMouseCapture};
+use crossterm::execute;
+use crossterm::terminal::{
+    EnterAlternateScreen, LeaveAlternateScreen, disable_raw_mode, enable_raw_mode,
+};
+use ratatui::Terminal;
+use ratatui::backend::CrosstermBackend;
+use std::fs::{File, OpenOptions};
+use std::io::{self, IsTerminal, Write};
+
+/// The terminal the UI is drawn on.
+pub type Tui = Terminal<CrosstermBackend<TtyWriter>>;
+
+/// Stdout, or `/dev/tty` when stdout is redirected.
+///
+/// Drawing on the tty keeps stdout free for `--print-last-dir` and picker
+/// output, so `cd "$(cb-ls --print-last-dir)"` works.
+pub enum TtyWriter {
+    Stdout(io::Stdout),
+    Tty(File),
+}
+
+impl TtyWriter {
+    pub fn open() -> io::Result<TtyWriter> {
+        if io::stdout().is_terminal() {
+            Ok(TtyWriter::Stdout(io::stdout()))
+        } else {
+            let tty = OpenOptions::new().read(true).write(true).open("/dev/tty")?;
+            Ok(TtyWriter::Tty(tty))
+        }
+    }
+}
+
+impl Write for TtyWriter {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        match self {
+            TtyWriter::Stdout(stdout) => stdout.write(buf),
+            TtyWriter::Tty(tty) => tty.write(buf),
+        }
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        match self {
+            TtyWriter::Stdout(stdout) => stdout.flush(),
+            TtyWriter::Tty(tty) => tty.flush(),
+        }
+    }
+}
+
+/// Enters raw mode and the alternate screen, like `ratatui::init`, and
+/// turns on mouse reporting.
+pub fn init() -> io::Result<Tui> {
+    let hook = std::panic::take_hook();
+    std::panic::set_hook(Box::new(move |info| {
+        restore();
+        hook(info);
+    }));
+
+    enable_raw_mode()?;
+    let mut writer = TtyWriter::open()?;
+    execute!(writer, EnterAlternateScreen, EnableMouseCapture)?;
+    Terminal::new(CrosstermBackend::new(writer))
+}
+
+pub fn restore() {
+    if let Err(err) = suspend() {
+        eprintln!("Failed to restore terminal: {}", err);
+    }
+}
+
+/// Hands the terminal back to the shell so a program can run in the foreground.
+pub fn suspend() -> io::Result<()> {
+    disable_raw_mode()?;
+    let mut writer = TtyWriter::open()?;
+    execute!(writer, DisableMouseCapture, LeaveAlternateScreen)
+}
+
+/// Takes the terminal back after [`suspend`] and forces a full redraw.
+pub fn resume(terminal: &mut Tui) -> io::Result<()> {
+    enable_raw_mode()?;
+    execute!(
+        terminal.backend_mut(),
+        EnterAlternateScreen,
+        EnableMouseCapture
+    )?;
+    terminal.clear()
+}
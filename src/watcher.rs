@@ -0,0 +1,93 @@
+use crate::events::AppEvent;
+use notify::event::EventKind;
+use notify::{RecommendedWatcher, RecursiveMode, Watcher};
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::Sender;
+use std::time::{Duration, Instant};
+
+/// How long the directory has to be quiet before it is read again.
+const DEBOUNCE: Duration = Duration::from_millis(150);
+/// Re-read at least this often while something keeps writing.
+const MAX_DELAY: Duration = Duration::from_millis(1000);
+
+/// Watches the browsed directory and the previewed entry with inotify.
+pub struct FsWatcher {
+    /// `None` if the platform or inotify limits don't allow watching.
+    watcher: Option<RecommendedWatcher>,
+    dir: Option<PathBuf>,
+    preview: Option<PathBuf>,
+    /// When the first and the latest unhandled change came in.
+    pending: Option<(Instant, Instant)>,
+}
+
+impl FsWatcher {
+    /// Reports changes as [`AppEvent::FsChange`] through `sender`.
+    pub fn new(sender: Sender<AppEvent>) -> FsWatcher {
+        let watcher = notify::recommended_watcher(move |event: notify::Result<notify::Event>| {
+            if let Ok(event) = event
+                && !matches!(event.kind, EventKind::Access(_))
+            {
+                let _ = sender.send(AppEvent::FsChange);
+            }
+        });
+
+        Self {
+            watcher: watcher.ok(),
+            dir: None,
+            preview: None,
+            pending: None,
+        }
+    }
+
+    /// Follows `dir` for the listing and `preview` for the preview pane,
+    /// dropping the previous watches when they changed.
+    pub fn watch(&mut self, dir: &Path, preview: Option<&Path>) {
+        let Some(watcher) = &mut self.watcher else {
+            return;
+        };
+
+        if self.dir.as_deref() != Some(dir) {
+            if let Some(old) = self.dir.take() {
+                let _ = watcher.unwatch(&old);
+            }
+            if watcher.watch(dir, RecursiveMode::NonRecursive).is_ok() {
+                self.dir = Some(dir.to_path_buf());
+            }
+        }
+
+        // entries of `dir` are already covered by its own watch
+        let preview = preview.filter(|path| path.is_dir());
+        if self.preview.as_deref() != preview {
+            if let Some(old) = self.preview.take() {
+                let _ = watcher.unwatch(&old);
+            }
+            if let Some(path) = preview
+                && watcher.watch(path, RecursiveMode::NonRecursive).is_ok()
+            {
+                self.preview = Some(path.to_path_buf());
+            }
+        }
+    }
+
+    /// Notes an [`AppEvent::FsChange`], the re-read waits for [`FsWatcher::settled`].
+    pub fn record(&mut self) {
+        let now = Instant::now();
+        self.pending = match self.pending {
+            Some((first, _)) => Some((first, now)),
+            None => Some((now, now)),
+        };
+    }
+
+    /// True once a burst of changes has settled and the listing should be read again.
+    pub fn settled(&mut self) -> bool {
+        let now = Instant::now();
+
+        match self.pending {
+            Some((first, latest)) if now - latest >= DEBOUNCE || now - first >= MAX_DELAY => {
+                self.pending = None;
+                true
+            }
+            _ => false,
+        }
+    }
+}